@@ -0,0 +1,102 @@
+//! Directory arguments, as typed on the command line, turned into projects.
+//!
+//! Each argument goes through brace expansion, placeholders and Unicode NFC
+//! normalization, may be a tree expression naming several directories, and
+//! is finally spelled the way `--slug` or `--case` asks.
+
+use crate::error::DrakoError;
+use crate::naming::{self, Case};
+use crate::plan::{Entry, ProjectSpec};
+use crate::{expand, placeholders, tree};
+use std::path::Path;
+
+/// How [`expand_args`] reads directory arguments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgOptions {
+    /// Take the arguments literally: no braces, placeholders or trees.
+    pub no_expand: bool,
+    /// Spelling for directory names, from `--slug` or `--case`.
+    pub case: Option<Case>,
+}
+
+/// The directories `args` stand for once braces and placeholders are filled
+/// in, in NFC.
+pub fn expand_names(args: &[String], options: ArgOptions) -> Result<Vec<String>, DrakoError> {
+    let mut names = Vec::new();
+    for arg in args {
+        if options.no_expand {
+            names.push(naming::nfc(arg));
+            continue;
+        }
+        for name in expand::expand(arg)? {
+            let name = placeholders::substitute(&name, &names)?;
+            names.push(naming::nfc(&name));
+        }
+    }
+    Ok(names)
+}
+
+/// One project for every directory `args` name, each a copy of `base` with
+/// its own `dir`, `title` and tree entries, followed by `base.entries`.
+pub fn expand_args(
+    args: &[String],
+    options: ArgOptions,
+    base: &ProjectSpec,
+) -> Result<Vec<ProjectSpec>, DrakoError> {
+    let mut projects = Vec::new();
+    for name in expand_names(args, options)? {
+        if options.no_expand || !tree::is_tree(&name) {
+            projects.push((name, Vec::new()));
+            continue;
+        }
+        let nodes = tree::parse(&name).map_err(|e| DrakoError::Usage(e.to_string()))?;
+        projects.extend(nodes.into_iter().map(|node| {
+            let entries = node.descendants().into_iter().map(Entry::dir).collect();
+            (node.name, entries)
+        }));
+    }
+
+    Ok(projects
+        .into_iter()
+        .map(|(dir, entries): (String, Vec<Entry>)| {
+            // The names as typed, before `--slug` or `--case`, go into templates.
+            let (dir, title, entries) = match options.case {
+                None => (dir, None, entries),
+                Some(case) => {
+                    let (dir, title) = rename(&dir, case);
+                    let entries = entries
+                        .into_iter()
+                        .map(|entry| Entry {
+                            path: entry
+                                .path
+                                .iter()
+                                .map(|name| case.apply(&name.to_string_lossy()))
+                                .collect(),
+                            ..entry
+                        })
+                        .collect();
+                    (dir, Some(title), entries)
+                }
+            };
+            ProjectSpec {
+                dir,
+                title,
+                entries: entries.into_iter().chain(base.entries.clone()).collect(),
+                ..base.clone()
+            }
+        })
+        .collect())
+}
+
+/// `dir` with its own name spelled in `case`, and that name as typed.
+fn rename(dir: &str, case: Case) -> (String, String) {
+    let path = Path::new(dir);
+    match path.file_name() {
+        Some(name) => {
+            let name = name.to_string_lossy().into_owned();
+            let renamed = path.with_file_name(case.apply(&name));
+            (renamed.to_string_lossy().into_owned(), name)
+        }
+        None => (dir.to_string(), dir.to_string()),
+    }
+}
@@ -0,0 +1,406 @@
+use crate::files::ConflictRules;
+use crate::flags::{self, Action, ArgKind, FlagSpec};
+use crate::json::OutputFormat;
+use crate::mode::Mode;
+use crate::naming::Case;
+use crate::owner::Owner;
+use crate::xattr;
+use std::fmt;
+
+/// Which mode drako runs in. `drako <dir>` is shorthand for `drako new <dir>`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Create new directories and set them up.
+    #[default]
+    New,
+    /// Set up directories that already exist.
+    Add,
+    /// List the files drako can generate.
+    TemplatesList,
+    /// Print the effective configuration.
+    ConfigShow,
+    /// Remove what the last run, or the run named in `dirs`, created.
+    Undo,
+    /// List the runs that can be undone.
+    UndoList,
+}
+
+/// A subcommand and the words that can follow it, for completion.
+#[derive(Debug)]
+pub struct Subcommand {
+    pub name: &'static str,
+    /// Fixed words allowed right after the subcommand.
+    pub words: &'static [&'static str],
+    pub help: &'static str,
+}
+
+/// Every subcommand [`parse`] recognizes as the first argument.
+pub const SUBCOMMANDS: &[Subcommand] = &[
+    Subcommand {
+        name: "new",
+        words: &[],
+        help: "Create directories and set them up.",
+    },
+    Subcommand {
+        name: "add",
+        words: &[],
+        help: "Set up directories that already exist.",
+    },
+    Subcommand {
+        name: "templates",
+        words: &["list"],
+        help: "List the files drako can generate.",
+    },
+    Subcommand {
+        name: "config",
+        words: &["show"],
+        help: "Show the config file and its defaults.",
+    },
+    Subcommand {
+        name: "undo",
+        words: &["list"],
+        help: "Remove what the last run created.",
+    },
+    Subcommand {
+        name: "help",
+        words: &[],
+        help: "Explain a single option.",
+    },
+];
+
+/// Everything drako was asked to do, as parsed from the command line.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub command: Command,
+    pub dirs: Vec<String>,
+    /// Project flags in the order they were given.
+    pub flags: Vec<&'static FlagSpec>,
+    pub mode: Option<Mode>,
+    /// Mode for the missing parents of each directory.
+    pub parents_mode: Option<Mode>,
+    /// From `--owner` and `--group`, already looked up.
+    pub owner: Option<Owner>,
+    /// `--xattr` attributes, as name and value.
+    pub xattrs: Vec<(String, String)>,
+    pub xattr_files: bool,
+    /// Set directories up for their group to share, from `--shared`.
+    pub shared: bool,
+    /// Carry on when a directory already exists.
+    pub exist_ok: bool,
+    /// `--conflict` values, already validated, applied over the config file.
+    pub conflict_rules: Vec<String>,
+    pub dry_run: bool,
+    /// Build each new directory in a staging directory first.
+    pub atomic: bool,
+    /// Take directory arguments literally.
+    pub no_expand: bool,
+    /// Spelling for directory names, from `--slug` or `--case`.
+    pub case: Option<Case>,
+    /// Make non-portable names an error rather than a warning.
+    pub portable: bool,
+    /// Tree file to lay out inside each directory.
+    pub from_tree: Option<String>,
+    /// Directory whose layout is copied.
+    pub like: Option<String>,
+    pub copy_modes: bool,
+    /// Placeholder file for empty leaf directories, from `--keep`.
+    pub keep: Option<String>,
+    pub output: OutputFormat,
+    pub verbose: bool,
+    pub help: bool,
+    /// Option named by `drako help <topic>`.
+    pub help_topic: Option<String>,
+    pub man: bool,
+    pub version: bool,
+    /// Shell to print a completion script for.
+    pub completion: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownOption(String),
+    InvalidMode(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+    UnknownSubcommand(String),
+    InvalidValue(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownOption(arg) => write!(f, "Unknown flag: {}", arg),
+            ParseError::InvalidMode(arg) => write!(f, "Invalid permission format: {}", arg),
+            ParseError::MissingValue(arg) => write!(f, "Option {} requires a value", arg),
+            ParseError::UnexpectedValue(arg) => write!(f, "Option {} does not take a value", arg),
+            ParseError::UnknownSubcommand(arg) => write!(f, "Unknown subcommand: {}", arg),
+            ParseError::InvalidValue(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Parse command line arguments, not including the program name.
+///
+/// Supports `--opt`, `--opt=value`, `--opt value`, bundled short flags
+/// (`-gr`), the `-###` permission shorthand and `--` to end options.
+pub fn parse<I, S>(args: I) -> Result<Options, ParseError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut options = Options::default();
+    let mut args = args.into_iter().map(Into::into).peekable();
+
+    // Subcommands are only recognized as the first argument; use
+    // `drako -- new` or `drako ./new` for a directory with the same name.
+    match args.peek().map(String::as_str) {
+        Some("help") => {
+            args.next();
+            options.help = true;
+            options.help_topic = args.next();
+            return Ok(options);
+        }
+        Some("new") => {
+            args.next();
+        }
+        Some("add") => {
+            args.next();
+            options.command = Command::Add;
+        }
+        Some("templates") => {
+            args.next();
+            options.command = subcommand(&mut args, "templates", "list", Command::TemplatesList)?;
+        }
+        Some("undo") => {
+            args.next();
+            options.command = match args.peek().map(String::as_str) {
+                Some("list") => {
+                    args.next();
+                    Command::UndoList
+                }
+                _ => Command::Undo,
+            };
+        }
+        Some("config") => {
+            args.next();
+            options.command = subcommand(&mut args, "config", "show", Command::ConfigShow)?;
+        }
+        _ => {}
+    }
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            options.dirs.extend(args.by_ref());
+            break;
+        }
+
+        if let Some(body) = arg.strip_prefix("--") {
+            let (name, inline) = match body.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (body, None),
+            };
+            let Some(spec) = flags::lookup(&format!("--{}", name)) else {
+                return Err(ParseError::UnknownOption(arg));
+            };
+            let value = match (spec.arg, inline) {
+                (ArgKind::None, Some(_)) => return Err(ParseError::UnexpectedValue(arg)),
+                (ArgKind::None, None) => None,
+                (ArgKind::Value(_), Some(value)) => Some(value),
+                (ArgKind::Value(_), None) => match args.next() {
+                    Some(value) => Some(value),
+                    None => return Err(ParseError::MissingValue(arg)),
+                },
+            };
+            apply(&mut options, spec, value)?;
+            continue;
+        }
+
+        let Some(body) = arg.strip_prefix('-').filter(|body| !body.is_empty()) else {
+            if arg == "-" {
+                return Err(ParseError::UnknownOption(arg));
+            }
+            options.dirs.push(arg);
+            continue;
+        };
+
+        // Whole-token matches come first so multi-letter shorts like `-go` win.
+        if let Some(spec) = flags::lookup(&arg) {
+            let value = match spec.arg {
+                ArgKind::None => None,
+                ArgKind::Value(_) => match args.next() {
+                    Some(value) => Some(value),
+                    None => return Err(ParseError::MissingValue(arg)),
+                },
+            };
+            apply(&mut options, spec, value)?;
+            continue;
+        }
+
+        if body.starts_with(|c: char| c.is_ascii_digit()) {
+            options.mode = Some(parse_mode(body, &arg)?);
+            continue;
+        }
+
+        parse_bundle(&mut options, body, &arg, &mut args)?;
+    }
+
+    Ok(options)
+}
+
+/// Parse the action word after `templates` or `config`. It may be left out.
+fn subcommand(
+    args: &mut std::iter::Peekable<impl Iterator<Item = String>>,
+    group: &str,
+    action: &str,
+    command: Command,
+) -> Result<Command, ParseError> {
+    match args.peek() {
+        Some(word) if word == action => {
+            args.next();
+            Ok(command)
+        }
+        Some(word) if !word.starts_with('-') => {
+            Err(ParseError::UnknownSubcommand(format!("{} {}", group, word)))
+        }
+        _ => Ok(command),
+    }
+}
+
+/// Expand bundled single-letter flags such as `-gr` into `-g -r`.
+fn parse_bundle(
+    options: &mut Options,
+    body: &str,
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<(), ParseError> {
+    let mut specs = Vec::new();
+    for (i, c) in body.char_indices() {
+        let Some(spec) = flags::lookup(&format!("-{}", c)) else {
+            return Err(ParseError::UnknownOption(arg.to_string()));
+        };
+        if let ArgKind::Value(_) = spec.arg {
+            // The rest of the token, or the next argument, is the value.
+            let rest = &body[i + c.len_utf8()..];
+            let value = if rest.is_empty() {
+                match args.next() {
+                    Some(value) => value,
+                    None => return Err(ParseError::MissingValue(format!("-{}", c))),
+                }
+            } else {
+                rest.to_string()
+            };
+            specs.push((spec, Some(value)));
+            break;
+        }
+        specs.push((spec, None));
+    }
+
+    for (spec, value) in specs {
+        apply(options, spec, value)?;
+    }
+    Ok(())
+}
+
+fn apply(
+    options: &mut Options,
+    spec: &'static FlagSpec,
+    value: Option<String>,
+) -> Result<(), ParseError> {
+    match spec.action {
+        Action::Help => options.help = true,
+        Action::Version => options.version = true,
+        Action::Verbose => options.verbose = true,
+        Action::Completion => options.completion = value,
+        Action::Man => options.man = true,
+        Action::ExistOk => options.exist_ok = true,
+        Action::DryRun => options.dry_run = true,
+        Action::Atomic => options.atomic = true,
+        Action::NoExpand => options.no_expand = true,
+        Action::Portable => options.portable = true,
+        Action::Slug => options.case = Some(Case::Kebab),
+        Action::Case => {
+            options.case = Some(
+                value
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(ParseError::InvalidValue)?,
+            )
+        }
+        Action::FromTree => options.from_tree = value,
+        Action::Like => options.like = value,
+        Action::CopyModes => options.copy_modes = true,
+        Action::Keep => {
+            options.keep.get_or_insert_with(|| ".gitkeep".to_string());
+        }
+        Action::KeepName => {
+            let name = value.unwrap_or_default();
+            if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+                return Err(ParseError::InvalidValue(format!(
+                    "Invalid placeholder name: {} (expected a file name such as .keep)",
+                    name
+                )));
+            }
+            options.keep = Some(name);
+        }
+        Action::XattrFiles => options.xattr_files = true,
+        Action::Xattr => options
+            .xattrs
+            .push(xattr::parse(&value.unwrap_or_default()).map_err(ParseError::InvalidValue)?),
+        Action::Output => {
+            options.output = value
+                .unwrap_or_default()
+                .parse()
+                .map_err(ParseError::InvalidValue)?
+        }
+        Action::Owner => {
+            let owner: Owner = value
+                .unwrap_or_default()
+                .parse()
+                .map_err(ParseError::InvalidValue)?;
+            let current = options.owner.get_or_insert_with(Owner::default);
+            current.user = owner.user;
+            current.group = owner.group.or(current.group.take());
+        }
+        Action::Shared => {
+            options.shared = true;
+            options
+                .owner
+                .get_or_insert_with(Owner::default)
+                .set_group(&value.unwrap_or_default())
+                .map_err(ParseError::InvalidValue)?
+        }
+        Action::Group => options
+            .owner
+            .get_or_insert_with(Owner::default)
+            .set_group(&value.unwrap_or_default())
+            .map_err(ParseError::InvalidValue)?,
+        Action::Conflict => {
+            let value = value.unwrap_or_default();
+            ConflictRules::default()
+                .add_rule(&value)
+                .map_err(ParseError::InvalidValue)?;
+            options.conflict_rules.push(value);
+        }
+        Action::Mode | Action::ParentsMode => {
+            let value = value.unwrap_or_default();
+            let mode = parse_mode(&value, &format!("--{}={}", spec.long, value))?;
+            match spec.action {
+                Action::Mode => options.mode = Some(mode),
+                _ => options.parents_mode = Some(mode),
+            }
+        }
+        Action::Run(_) | Action::Write(_) => {
+            if !options.flags.iter().any(|f| std::ptr::eq(*f, spec)) {
+                options.flags.push(spec);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse a mode given as `display` on the command line.
+fn parse_mode(value: &str, display: &str) -> Result<Mode, ParseError> {
+    value
+        .parse()
+        .map_err(|reason| ParseError::InvalidMode(format!("{} ({})", display, reason)))
+}
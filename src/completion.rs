@@ -0,0 +1,424 @@
+use crate::cli::SUBCOMMANDS;
+use crate::files::ConflictPolicy;
+use crate::flags::{Action, ArgKind, FlagSpec, FLAGS};
+use crate::json::OutputFormat;
+use crate::naming::Case;
+use std::fmt;
+use std::str::FromStr;
+
+/// Shells drako can generate completion scripts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 4] = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::PowerShell => "powershell",
+        }
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" | "pwsh" => Ok(Shell::PowerShell),
+            _ => Err(format!(
+                "Unsupported shell: {} (expected bash, zsh, fish or powershell)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Permission modes suggested for `--mode` and the `-###` shorthand.
+pub const MODE_HINTS: &[&str] = &["755", "750", "700", "775", "770", "777", "2775"];
+
+/// Values offered after an option that takes one.
+pub fn value_hints(spec: &FlagSpec) -> Vec<String> {
+    match spec.action {
+        Action::Mode | Action::ParentsMode => MODE_HINTS.iter().map(|m| m.to_string()).collect(),
+        Action::Completion => Shell::ALL.iter().map(|s| s.name().to_string()).collect(),
+        Action::Conflict => ConflictPolicy::ALL
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect(),
+        Action::Case => Case::ALL.iter().map(|c| c.name().to_string()).collect(),
+        Action::Output => OutputFormat::ALL
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every spelling of every option, dash included.
+fn all_names() -> Vec<String> {
+    let mut names = Vec::new();
+    for spec in FLAGS {
+        names.push(format!("--{}", spec.long));
+        names.extend(spec.short.iter().map(|s| format!("-{}", s)));
+    }
+    names.extend(MODE_HINTS.iter().map(|m| format!("-{}", m)));
+    names
+}
+
+/// Words offered after a subcommand: its fixed words, or the options
+/// `drako help` explains.
+fn subcommand_words(name: &str) -> Vec<String> {
+    if name == "help" {
+        return FLAGS.iter().map(|spec| spec.long.to_string()).collect();
+    }
+    SUBCOMMANDS
+        .iter()
+        .filter(|sub| sub.name == name)
+        .flat_map(|sub| sub.words.iter().map(|word| word.to_string()))
+        .collect()
+}
+
+/// Subcommands that take a word after them.
+fn subcommands_with_words() -> impl Iterator<Item = (&'static str, Vec<String>)> {
+    SUBCOMMANDS
+        .iter()
+        .map(|sub| (sub.name, subcommand_words(sub.name)))
+        .filter(|(_, words)| !words.is_empty())
+}
+
+fn takes_value(spec: &FlagSpec) -> bool {
+    matches!(spec.arg, ArgKind::Value(_))
+}
+
+pub fn generate(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh => zsh(),
+        Shell::Fish => fish(),
+        Shell::PowerShell => powershell(),
+    }
+}
+
+fn bash() -> String {
+    let mut subcommand_cases = String::new();
+    for (name, words) in subcommands_with_words() {
+        subcommand_cases.push_str(&format!(
+            "            {})\n                COMPREPLY=( $(compgen -W \"{}\" -- \"$cur\") )\n                return\n                ;;\n",
+            name,
+            words.join(" ")
+        ));
+    }
+    let subcommands: Vec<&str> = SUBCOMMANDS.iter().map(|sub| sub.name).collect();
+
+    let mut value_cases = String::new();
+    for spec in FLAGS.iter().filter(|s| takes_value(s)) {
+        // Options without fixed values take a file name.
+        let words = match value_hints(spec).join(" ") {
+            _ if spec.action == Action::Owner => "-u".to_string(),
+            _ if matches!(spec.action, Action::Group | Action::Shared) => "-g".to_string(),
+            hints if hints.is_empty() => "-f".to_string(),
+            hints => format!("-W \"{}\"", hints),
+        };
+        value_cases.push_str(&format!(
+            "        --{})\n            COMPREPLY=( $(compgen {} -- \"$cur\") )\n            return\n            ;;\n",
+            spec.long, words
+        ));
+    }
+
+    format!(
+        r#"_drako() {{
+    local cur prev
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+
+    case "$prev" in
+{value_cases}    esac
+
+    if [[ $COMP_CWORD -eq 2 ]]; then
+        case "${{COMP_WORDS[1]}}" in
+{subcommand_cases}        esac
+    fi
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=( $(compgen -W "{names}" -- "$cur") )
+        return
+    fi
+
+    if [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=( $(compgen -W "{subcommands}" -- "$cur") )
+    fi
+    COMPREPLY+=( $(compgen -d -- "$cur") )
+}}
+
+complete -o filenames -F _drako drako
+"#,
+        names = all_names().join(" "),
+        subcommands = subcommands.join(" "),
+    )
+}
+
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh() -> String {
+    let mut specs = String::new();
+    for spec in FLAGS {
+        let mut names: Vec<String> = vec![format!("--{}", spec.long)];
+        names.extend(spec.short.iter().map(|s| format!("-{}", s)));
+        let help = zsh_escape(spec.help);
+        let value = match spec.arg {
+            ArgKind::None => String::new(),
+            ArgKind::Value(name) => match value_hints(spec).join(" ") {
+                _ if spec.action == Action::Owner => format!(":{}:_users", name),
+                _ if matches!(spec.action, Action::Group | Action::Shared) => {
+                    format!(":{}:_groups", name)
+                }
+                hints if hints.is_empty() => format!(":{}:_files", name),
+                hints => format!(":{}:({})", name, hints),
+            },
+        };
+        let equals = if takes_value(spec) { "=" } else { "" };
+
+        if names.len() == 1 {
+            specs.push_str(&format!(
+                "    '{}{}[{}]{}' \\\n",
+                names[0], equals, help, value
+            ));
+        } else {
+            specs.push_str(&format!(
+                "    '({})'{{{}}}'[{}]{}' \\\n",
+                names.join(" "),
+                names.join(","),
+                help,
+                value
+            ));
+        }
+    }
+    for mode in MODE_HINTS {
+        specs.push_str(&format!(
+            "    '-{}[Set directory permissions to {}]' \\\n",
+            mode, mode
+        ));
+    }
+    let subcommands: Vec<String> = SUBCOMMANDS
+        .iter()
+        .map(|sub| format!("'{}:{}'", sub.name, zsh_escape(sub.help)))
+        .collect();
+    let mut subcommand_cases = String::new();
+    for (name, words) in subcommands_with_words() {
+        subcommand_cases.push_str(&format!(
+            "      {}) compadd -- {}; return ;;\n",
+            name,
+            words.join(" ")
+        ));
+    }
+
+    format!(
+        r#"#compdef drako
+
+_drako() {{
+  local -a subcommands
+  subcommands=({subcommands})
+  if (( CURRENT == 2 )) && [[ $PREFIX != -* ]]; then
+    _describe -t subcommands subcommand subcommands
+    _directories
+    return
+  fi
+  if (( CURRENT == 3 )); then
+    case $words[2] in
+{subcommand_cases}    esac
+  fi
+
+  _arguments -s \
+{specs}    '*:directory:_directories'
+}}
+
+if [ "$funcstack[1]" = "_drako" ]; then
+  _drako "$@"
+else
+  compdef _drako drako
+fi
+"#,
+        subcommands = subcommands.join(" "),
+    )
+}
+
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish() -> String {
+    let mut script = String::from(
+        "complete -c drako -f\ncomplete -c drako -a '(__fish_complete_directories)'\n",
+    );
+    for spec in FLAGS {
+        let mut line = format!("complete -c drako -l {}", spec.long);
+        for short in spec.short {
+            // fish only treats single letters as short options; longer ones are old-style.
+            let kind = if short.chars().count() == 1 {
+                "-s"
+            } else {
+                "-o"
+            };
+            line.push_str(&format!(" {} {}", kind, short));
+        }
+        match value_hints(spec).join(" ") {
+            _ if !takes_value(spec) => {}
+            _ if spec.action == Action::Owner => line.push_str(" -x -a '(__fish_complete_users)'"),
+            _ if matches!(spec.action, Action::Group | Action::Shared) => {
+                line.push_str(" -x -a '(__fish_complete_groups)'")
+            }
+            hints if hints.is_empty() => line.push_str(" -r -F"),
+            hints => line.push_str(&format!(" -x -a '{}'", hints)),
+        }
+        line.push_str(&format!(" -d '{}'\n", fish_escape(spec.help)));
+        script.push_str(&line);
+    }
+    for mode in MODE_HINTS {
+        script.push_str(&format!(
+            "complete -c drako -o {} -d 'Set directory permissions to {}'\n",
+            mode, mode
+        ));
+    }
+    for sub in SUBCOMMANDS {
+        script.push_str(&format!(
+            "complete -c drako -n '__fish_use_subcommand' -a {} -d '{}'\n",
+            sub.name,
+            fish_escape(sub.help)
+        ));
+    }
+    for (name, words) in subcommands_with_words() {
+        script.push_str(&format!(
+            "complete -c drako -n '__fish_seen_subcommand_from {}' -a '{}'\n",
+            name,
+            words.join(" ")
+        ));
+    }
+    script
+}
+
+fn powershell_escape(text: &str) -> String {
+    text.replace('\'', "''")
+}
+
+fn powershell() -> String {
+    let mut options = String::new();
+    for spec in FLAGS {
+        let help = powershell_escape(spec.help);
+        options.push_str(&format!("        @('--{}', '{}')\n", spec.long, help));
+        for short in spec.short {
+            options.push_str(&format!("        @('-{}', '{}')\n", short, help));
+        }
+    }
+    for mode in MODE_HINTS {
+        options.push_str(&format!(
+            "        @('-{}', 'Set directory permissions to {}')\n",
+            mode, mode
+        ));
+    }
+
+    let mut subcommands = String::new();
+    for sub in SUBCOMMANDS {
+        subcommands.push_str(&format!(
+            "        @('{}', '{}')\n",
+            sub.name,
+            powershell_escape(sub.help)
+        ));
+    }
+    let mut subcommand_cases = String::new();
+    for (name, words) in subcommands_with_words() {
+        let words = words
+            .iter()
+            .map(|w| format!("'{}'", w))
+            .collect::<Vec<_>>()
+            .join(", ");
+        subcommand_cases.push_str(&format!(
+            "            '{}' {{ $values = @({}) }}\n",
+            name, words
+        ));
+    }
+
+    let mut value_cases = String::new();
+    for spec in FLAGS
+        .iter()
+        .filter(|s| takes_value(s) && !value_hints(s).is_empty())
+    {
+        let values = value_hints(spec)
+            .iter()
+            .map(|v| format!("'{}'", powershell_escape(v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        value_cases.push_str(&format!(
+            "        '--{}' {{ $values = @({}) }}\n",
+            spec.long, values
+        ));
+    }
+
+    format!(
+        r#"Register-ArgumentCompleter -Native -CommandName drako -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $options = @(
+{options}    )
+
+    $subcommands = @(
+{subcommands}    )
+
+    $before = @($commandAst.CommandElements |
+        Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }})
+    $previous = $before | Select-Object -Last 1
+    $values = $null
+    switch ("$previous") {{
+{value_cases}    }}
+    if (-not $values -and $before.Count -eq 2) {{
+        switch ("$previous") {{
+{subcommand_cases}        }}
+    }}
+    if ($values) {{
+        $values | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+        }}
+        return
+    }}
+
+    if ($wordToComplete -like '-*') {{
+        $options | Where-Object {{ $_[0] -like "$wordToComplete*" }} | ForEach-Object {{
+            [System.Management.Automation.CompletionResult]::new($_[0], $_[0], 'ParameterName', $_[1])
+        }}
+        return
+    }}
+
+    if ($before.Count -eq 1) {{
+        $subcommands | Where-Object {{ $_[0] -like "$wordToComplete*" }} | ForEach-Object {{
+            [System.Management.Automation.CompletionResult]::new($_[0], $_[0], 'Command', $_[1])
+        }}
+    }}
+    Get-ChildItem -Directory -Path "$wordToComplete*" -ErrorAction SilentlyContinue | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ProviderContainer', $_.Name)
+    }}
+}}
+"#
+    )
+}
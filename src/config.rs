@@ -0,0 +1,106 @@
+use crate::error::DrakoError;
+use crate::files::ConflictRules;
+use crate::flags::{self, FlagSpec};
+use crate::mode::Mode;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Defaults read from the config file.
+///
+/// The file holds `key = value` lines; `#` starts a comment:
+///
+/// ```text
+/// # Applied to every new project
+/// flags = --git --readme
+/// mode = 755
+/// conflict = backup
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    /// Where the config was looked for, if anywhere.
+    pub path: Option<PathBuf>,
+    /// Whether that file exists.
+    pub loaded: bool,
+    /// Project flags applied to every new project.
+    pub flags: Vec<&'static FlagSpec>,
+    pub mode: Option<Mode>,
+    /// Conflict policy for existing files, before `--conflict` is applied.
+    pub conflicts: ConflictRules,
+}
+
+/// `$DRAKO_CONFIG`, else `$XDG_CONFIG_HOME/drako/config`, else `~/.config/drako/config`.
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("DRAKO_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("drako").join("config"))
+}
+
+/// Load the config file. A missing file is not an error.
+pub fn load() -> Result<Config, DrakoError> {
+    let mut config = Config {
+        path: config_path(),
+        ..Config::default()
+    };
+    let Some(path) = config.path.clone() else {
+        return Ok(config);
+    };
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(config),
+        Err(e) => return Err(DrakoError::io(&path, e)),
+    };
+    config.loaded = true;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |message: String| DrakoError::Config {
+            path: path.clone(),
+            line: number + 1,
+            message,
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(invalid(format!("expected `key = value`, found `{}`", line)));
+        };
+        let value = value.trim();
+
+        match key.trim() {
+            "flags" => {
+                for name in value.split_whitespace() {
+                    match flags::lookup(name) {
+                        Some(spec) if spec.is_project_flag() => config.flags.push(spec),
+                        _ => return Err(invalid(format!("`{}` is not a project flag", name))),
+                    }
+                }
+            }
+            "mode" => {
+                // `-755` as on the command line.
+                let mode = value
+                    .strip_prefix('-')
+                    .filter(|m| m.starts_with(|c: char| c.is_ascii_digit()))
+                    .unwrap_or(value);
+                config.mode = Some(mode.parse().map_err(|reason| {
+                    invalid(format!("invalid permission format `{}`: {}", value, reason))
+                })?);
+            }
+            "conflict" => {
+                for rule in value.split_whitespace() {
+                    config.conflicts.add_rule(rule).map_err(invalid)?;
+                }
+            }
+            other => return Err(invalid(format!("unknown setting `{}`", other))),
+        }
+    }
+
+    Ok(config)
+}
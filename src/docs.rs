@@ -0,0 +1,237 @@
+use crate::completion::value_hints;
+use crate::error::EXIT_CODES;
+use crate::flags::{long_display, short_names, Action, ArgKind, FlagSpec, FLAGS};
+
+/// Directory name used in generated examples.
+const EXAMPLE_DIR: &str = "myproject";
+
+/// Shell commands an option runs inside each new directory.
+pub fn commands(spec: &FlagSpec) -> Vec<String> {
+    match spec.action {
+        Action::Run(cmd) => vec![cmd.replace("{dir}", "<dir>")],
+        _ => Vec::new(),
+    }
+}
+
+/// Files an option writes inside each new directory.
+pub fn files(spec: &FlagSpec) -> Vec<String> {
+    match spec.action {
+        Action::Write(template) => vec![format!("<dir>/{}", template.file_name())],
+        _ => Vec::new(),
+    }
+}
+
+/// Example invocations of an option.
+pub fn examples(spec: &FlagSpec) -> Vec<String> {
+    let value = value_hints(spec).into_iter().next().unwrap_or_default();
+    match spec.action {
+        Action::Help => vec!["drako --help".to_string(), "drako help go".to_string()],
+        Action::Version => vec!["drako --version".to_string()],
+        Action::Man => vec!["drako --man | man -l -".to_string()],
+        Action::Completion => vec![format!("eval \"$(drako --completion {})\"", value)],
+        Action::Mode => vec![
+            format!("drako {} -{}", EXAMPLE_DIR, value),
+            format!("drako {} --mode {}", EXAMPLE_DIR, value),
+        ],
+        Action::ParentsMode => vec![format!("drako srv/{} -750 --parents-mode 750", EXAMPLE_DIR)],
+        Action::Owner => vec![
+            format!("sudo drako {} --owner deploy:www-data", EXAMPLE_DIR),
+            format!("sudo drako {} --owner deploy:", EXAMPLE_DIR),
+        ],
+        Action::Group => vec![format!("drako {} --group developers -2775", EXAMPLE_DIR)],
+        Action::Shared => vec![format!("drako {} --shared developers", EXAMPLE_DIR)],
+        Action::Xattr => vec![format!(
+            "drako {} --xattr user.project=atlas --xattr user.owner-team=infra",
+            EXAMPLE_DIR
+        )],
+        Action::XattrFiles => vec![format!(
+            "drako {} -r --xattr user.project=atlas --xattr-files",
+            EXAMPLE_DIR
+        )],
+        Action::Like => vec![format!("drako {} --like ../service-a", EXAMPLE_DIR)],
+        Action::CopyModes => vec![format!(
+            "drako {} --like ../service-a --copy-modes",
+            EXAMPLE_DIR
+        )],
+        Action::Slug => vec!["drako \"My New Service\" --slug --readme".to_string()],
+        Action::Case => vec!["drako \"My New Service\" --case snake".to_string()],
+        Action::Keep => vec![format!(
+            "drako {} --from-tree layout.txt --keep",
+            EXAMPLE_DIR
+        )],
+        Action::KeepName => vec![format!(
+            "drako '{}(src,assets)' --keep --keep-name .keep",
+            EXAMPLE_DIR
+        )],
+        Action::FromTree => vec![format!("drako {} --from-tree layout.txt", EXAMPLE_DIR)],
+        Action::Output => vec![format!("drako {} --git --output json", EXAMPLE_DIR)],
+        Action::Conflict => vec![
+            format!("drako add {} --readme --conflict backup", EXAMPLE_DIR),
+            format!("drako add {} -r -l --conflict README.md=skip", EXAMPLE_DIR),
+        ],
+        _ => {
+            let mut examples = vec![format!("drako {} --{}", EXAMPLE_DIR, spec.long)];
+            examples.extend(
+                short_names(spec)
+                    .iter()
+                    .map(|short| format!("drako {} {}", EXAMPLE_DIR, short)),
+            );
+            examples
+        }
+    }
+}
+
+/// `drako help <topic>` text for one option.
+pub fn topic(spec: &FlagSpec) -> String {
+    let mut names = vec![long_display(spec)];
+    names.extend(short_names(spec));
+    let mut text = format!("{}\n    {}\n", names.join(", "), spec.help);
+
+    let sections = [
+        ("Runs in each new directory:", commands(spec)),
+        ("Writes:", files(spec)),
+        ("Examples:", examples(spec)),
+    ];
+    for (title, lines) in sections {
+        if lines.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\n{}\n", title));
+        for line in lines {
+            text.push_str(&format!("    {}\n", line));
+        }
+    }
+    text
+}
+
+/// Escape text for use in a roff document.
+fn roff(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// The drako man page in roff format.
+pub fn man_page() -> String {
+    let mut page = format!(
+        ".TH DRAKO 1 \"\" \"drako {}\" \"User Commands\"\n\
+         .SH NAME\n\
+         drako \\- {}\n\
+         .SH SYNOPSIS\n\
+         .B drako\n\
+         [\\fIOPTIONS\\fR] \\fIDIRECTORY\\fR...\n\
+         .br\n\
+         .B drako add\n\
+         [\\fIOPTIONS\\fR] \\fIDIRECTORY\\fR...\n\
+         .br\n\
+         .B drako templates list\n\
+         .br\n\
+         .B drako config show\n\
+         .br\n\
+         .B drako undo\n\
+         [\\fBlist\\fR | \\fIRUN\\fR]\n\
+         .br\n\
+         .B drako help\n\
+         [\\fIOPTION\\fR]\n\
+         .SH DESCRIPTION\n\
+         Creates one or more directories with optional project initialization.\n\
+         Multiple directories can be specified, and options apply to all of them.\n\
+         Short options can be combined (\\fB\\-gr\\fR), options that take a value accept\n\
+         \\fB\\-\\-opt=value\\fR or \\fB\\-\\-opt value\\fR, and \\fB\\-\\-\\fR ends option parsing.\n\
+         .PP\n\
+         \\fBdrako new\\fR is the same as plain \\fBdrako\\fR. \\fBdrako add\\fR applies the options\n\
+         to directories that already exist. Defaults for new projects are read from\n\
+         \\fI$XDG_CONFIG_HOME/drako/config\\fR (override with \\fBDRAKO_CONFIG\\fR).\n\
+         .SH OPTIONS\n",
+        env!("CARGO_PKG_VERSION"),
+        roff(env!("CARGO_PKG_DESCRIPTION")),
+    );
+
+    for spec in FLAGS {
+        let mut names = vec![match spec.arg {
+            ArgKind::None => format!("\\fB{}\\fR", roff(&format!("--{}", spec.long))),
+            ArgKind::Value(name) => format!(
+                "\\fB{}\\fR \\fI{}\\fR",
+                roff(&format!("--{}", spec.long)),
+                name
+            ),
+        }];
+        names.extend(
+            short_names(spec)
+                .iter()
+                .map(|short| format!("\\fB{}\\fR", roff(short))),
+        );
+        page.push_str(&format!(".TP\n{}\n{}\n", names.join(", "), roff(spec.help)));
+        for command in commands(spec) {
+            page.push_str(&format!("Runs \\fB{}\\fR.\n", roff(&command)));
+        }
+        for file in files(spec) {
+            page.push_str(&format!("Writes \\fI{}\\fR.\n", roff(&file)));
+        }
+    }
+
+    page.push_str(".SH EXIT STATUS\n");
+    for (code, meaning) in EXIT_CODES {
+        page.push_str(&format!(".TP\n.B {}\n{}\n", code, roff(meaning)));
+    }
+
+    page.push_str(".SH EXAMPLES\n");
+    for spec in FLAGS.iter().filter(|s| s.action != Action::Help) {
+        if let Some(example) = examples(spec).first() {
+            page.push_str(&format!(".TP\n.B {}\n{}\n", roff(example), roff(spec.help)));
+        }
+    }
+
+    page.push_str(".SH SEE ALSO\n.BR mkdir (1)\n");
+    page
+}
+
+/// The option table rendered as the Markdown table used in the README.
+pub fn markdown_table() -> String {
+    let mut rows = vec![(
+        "Flag".to_string(),
+        "Short".to_string(),
+        "Description".to_string(),
+    )];
+    for spec in FLAGS {
+        let short = short_names(spec)
+            .iter()
+            .map(|s| format!("`{}`", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        rows.push((
+            format!("`{}`", long_display(spec)),
+            short,
+            spec.help.trim_end_matches('.').to_string(),
+        ));
+    }
+
+    let widths = rows.iter().fold((0, 0, 0), |w, r| {
+        (w.0.max(r.0.len()), w.1.max(r.1.len()), w.2.max(r.2.len()))
+    });
+    let line = |a: &str, b: &str, c: &str| {
+        format!(
+            "| {:<w0$} | {:<w1$} | {:<w2$} |\n",
+            a,
+            b,
+            c,
+            w0 = widths.0,
+            w1 = widths.1,
+            w2 = widths.2
+        )
+    };
+
+    let mut table = line(&rows[0].0, &rows[0].1, &rows[0].2);
+    table.push_str(&line(
+        &"-".repeat(widths.0),
+        &"-".repeat(widths.1),
+        &"-".repeat(widths.2),
+    ));
+    for row in &rows[1..] {
+        table.push_str(&line(&row.0, &row.1, &row.2));
+    }
+    table
+}
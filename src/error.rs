@@ -0,0 +1,135 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Every exit code drako uses and what it means.
+pub const EXIT_CODES: &[(i32, &str)] = &[
+    (0, "Everything succeeded"),
+    (1, "Other I/O error (e.g. a parent path is a file)"),
+    (2, "Invalid command line or config file"),
+    (3, "A directory already existed"),
+    (4, "Permission denied"),
+    (5, "A required tool (git, npm, ...) is not installed"),
+    (6, "A setup command such as git init exited with an error"),
+    (7, "A template file could not be written"),
+    (8, "A directory passed to drako add does not exist"),
+    (9, "A file already existed and the conflict policy is fail, or drako undo kept something that changed"),
+];
+
+/// Everything that can go wrong while drako works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrakoError {
+    /// The command line could not be understood.
+    Usage(String),
+    /// The config file or a tree file could not be understood.
+    Config {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    AlreadyExists(PathBuf),
+    /// A directory that should exist does not.
+    NotFound(PathBuf),
+    /// A file drako wanted to write already exists.
+    Conflict(PathBuf),
+    PermissionDenied(PathBuf),
+    /// A command's program is not installed.
+    ToolMissing(String),
+    CommandFailed {
+        command: String,
+        dir: PathBuf,
+        status: Option<i32>,
+        stderr: String,
+    },
+    /// A template file could not be written.
+    Template {
+        path: PathBuf,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl DrakoError {
+    /// Classify an I/O error that happened at `path`.
+    pub fn io(path: &Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::AlreadyExists => DrakoError::AlreadyExists(path.to_path_buf()),
+            io::ErrorKind::PermissionDenied => DrakoError::PermissionDenied(path.to_path_buf()),
+            _ => DrakoError::Io {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Process exit code for this error. See the "Exit codes" section of the README.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            DrakoError::Io { .. } => 1,
+            DrakoError::Usage(_) | DrakoError::Config { .. } => 2,
+            DrakoError::AlreadyExists(_) => 3,
+            DrakoError::PermissionDenied(_) => 4,
+            DrakoError::ToolMissing(_) => 5,
+            DrakoError::CommandFailed { .. } => 6,
+            DrakoError::Template { .. } => 7,
+            DrakoError::NotFound(_) => 8,
+            DrakoError::Conflict(_) => 9,
+        }
+    }
+}
+
+impl fmt::Display for DrakoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrakoError::Usage(message) => write!(f, "{}", message),
+            DrakoError::Config {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            DrakoError::NotFound(path) => write!(f, "No such directory {}", path.display()),
+            DrakoError::Conflict(path) => write!(
+                f,
+                "File already exists {} (use --conflict to skip, overwrite or back it up)",
+                path.display()
+            ),
+            DrakoError::AlreadyExists(path) => {
+                write!(f, "Directory already exists {}", path.display())
+            }
+            DrakoError::PermissionDenied(path) => {
+                write!(f, "Permission denied {}", path.display())
+            }
+            DrakoError::ToolMissing(tool) => write!(f, "Command not found: {}", tool),
+            DrakoError::CommandFailed {
+                command,
+                dir,
+                status,
+                stderr,
+            } => {
+                match status {
+                    Some(code) => write!(
+                        f,
+                        "Failed: {} in {} (exit {})",
+                        command,
+                        dir.display(),
+                        code
+                    )?,
+                    None => write!(f, "Failed: {} in {}", command, dir.display())?,
+                }
+                if !stderr.trim().is_empty() {
+                    write!(f, "\n{}", stderr.trim_end())?;
+                }
+                Ok(())
+            }
+            DrakoError::Template { path, message } => {
+                write!(f, "Failed to create {}: {}", path.display(), message)
+            }
+            DrakoError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for DrakoError {}
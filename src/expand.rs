@@ -0,0 +1,147 @@
+//! Shell-style brace expansion for directory arguments.
+//!
+//! `proj/{src,tests}/{unit,e2e}` gives four paths, `day{01..30}` thirty
+//! zero-padded ones and `{a..e}` five letters. A range may end with a step,
+//! as in `{0..100..10}`. Braces that hold neither a comma nor a valid range,
+//! such as `{}` or `{x}`, are left as they are. A word may expand to at most
+//! [`MAX_NAMES`] names.
+
+use crate::error::DrakoError;
+
+/// The most names one word may expand to.
+pub const MAX_NAMES: usize = 10_000;
+
+fn too_many(word: &str) -> DrakoError {
+    DrakoError::Usage(format!("{} expands to more than {} names", word, MAX_NAMES))
+}
+
+/// Every word `word` expands to, in order.
+pub fn expand(word: &str) -> Result<Vec<String>, DrakoError> {
+    let mut search = 0;
+    while let Some(open) = word[search..].find('{').map(|i| i + search) {
+        if let Some(close) = matching_close(word, open) {
+            if let Some(alternatives) = alternatives(&word[open + 1..close])? {
+                let prefix = &word[..open];
+                let suffixes = expand(&word[close + 1..])?;
+                let mut words = Vec::new();
+                for alternative in alternatives {
+                    for middle in expand(&alternative)? {
+                        for suffix in &suffixes {
+                            if words.len() == MAX_NAMES {
+                                return Err(too_many(word));
+                            }
+                            words.push(format!("{}{}{}", prefix, middle, suffix));
+                        }
+                    }
+                }
+                return Ok(words);
+            }
+        }
+        search = open + 1;
+    }
+    Ok(vec![word.to_string()])
+}
+
+/// Index of the `}` closing the `{` at `open`.
+fn matching_close(word: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in word[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The choices a brace body stands for, or `None` if it isn't expandable.
+fn alternatives(body: &str) -> Result<Option<Vec<String>>, DrakoError> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(body[start..i].to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if parts.is_empty() {
+        return sequence(body);
+    }
+    parts.push(body[start..].to_string());
+    Ok(Some(parts))
+}
+
+/// Expand `a..b` or `a..b..step` over integers or single letters.
+fn sequence(body: &str) -> Result<Option<Vec<String>>, DrakoError> {
+    let parts: Vec<&str> = body.split("..").collect();
+    let (from, to, step) = match parts[..] {
+        [from, to] => (from, to, 1),
+        [from, to, step] => match step.parse::<i64>() {
+            Ok(step) => (from, to, step.unsigned_abs().max(1)),
+            Err(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let too_many = || too_many(&format!("{{{}}}", body));
+
+    if let (Ok(a), Ok(b)) = (from.parse::<i64>(), to.parse::<i64>()) {
+        let padded = |s: &str| s.trim_start_matches('-').starts_with('0') && s.len() > 1;
+        let width = if padded(from) || padded(to) {
+            from.len().max(to.len())
+        } else {
+            0
+        };
+        return Ok(Some(
+            range(a, b, step)
+                .ok_or_else(too_many)?
+                .map(|n| format!("{:0width$}", n, width = width))
+                .collect(),
+        ));
+    }
+
+    let letter = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Some(c as i64),
+            _ => None,
+        }
+    };
+    let (Some(a), Some(b)) = (letter(from), letter(to)) else {
+        return Ok(None);
+    };
+    Ok(Some(
+        range(a, b, step)
+            .ok_or_else(too_many)?
+            .filter_map(|n| char::from_u32(n as u32))
+            .map(String::from)
+            .collect(),
+    ))
+}
+
+/// `from` to `to` inclusive, counting down if `to` is smaller, or `None` if
+/// that is more than [`MAX_NAMES`] numbers.
+fn range(from: i64, to: i64, step: u64) -> Option<impl Iterator<Item = i64>> {
+    let steps = from.abs_diff(to) / step;
+    if steps >= MAX_NAMES as u64 {
+        return None;
+    }
+    let step = if to < from {
+        -i128::from(step)
+    } else {
+        i128::from(step)
+    };
+    // Every number lies between `from` and `to`, so it fits back into an i64.
+    Some((0..=steps).map(move |i| (i128::from(from) + i128::from(i) * step) as i64))
+}
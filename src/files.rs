@@ -1,44 +1,205 @@
-use crate::messages::*;
+use crate::error::DrakoError;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-pub fn create_readme(dir: &str, verbose: bool) -> Result<(), std::io::Error> {
-    let content = readme_content();
-    let path = Path::new(dir).join("README.md");
-    fs::write(path, content)?;
+/// Template files drako can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    Readme,
+    License,
+    Dockerfile,
+}
+
+impl Template {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Template::Readme => "README.md",
+            Template::License => "LICENSE",
+            Template::Dockerfile => "Dockerfile",
+        }
+    }
+
+    /// The file's contents. `title` names the project where the template
+    /// has a heading for it.
+    pub fn content(&self, title: Option<&str>) -> String {
+        match self {
+            Template::Readme => readme_content(title.unwrap_or("Project Title")),
+            Template::License => mit_content(),
+            Template::Dockerfile => docker_content(),
+        }
+    }
+}
+
+/// What to do when a file drako wants to write already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Leave the existing file alone.
+    Skip,
+    Overwrite,
+    /// Move the existing file to `<name>.bak` first.
+    Backup,
+    /// Ask on the terminal; fails when there is no terminal.
+    Prompt,
+    #[default]
+    Fail,
+}
+
+impl ConflictPolicy {
+    pub const ALL: [ConflictPolicy; 5] = [
+        ConflictPolicy::Skip,
+        ConflictPolicy::Overwrite,
+        ConflictPolicy::Backup,
+        ConflictPolicy::Prompt,
+        ConflictPolicy::Fail,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Overwrite => "overwrite",
+            ConflictPolicy::Backup => "backup",
+            ConflictPolicy::Prompt => "prompt",
+            ConflictPolicy::Fail => "fail",
+        }
+    }
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ConflictPolicy::ALL
+            .into_iter()
+            .find(|policy| policy.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown conflict policy: {} (expected skip, overwrite, backup, prompt or fail)",
+                    s
+                )
+            })
+    }
+}
 
-    if verbose {
-        success("Created README.md in", Some(dir));
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
     }
-    Ok(())
 }
 
-pub fn create_mitfile(dir: &str, verbose: bool) -> Result<(), std::io::Error> {
-    let content = mit_content();
-    let path = Path::new(dir).join("LICENSE");
-    fs::write(path, content)?;
+/// A default conflict policy plus overrides for individual file names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictRules {
+    pub default: ConflictPolicy,
+    pub files: Vec<(String, ConflictPolicy)>,
+}
 
-    if verbose {
-        success("Created LICENSE in", Some(dir));
+impl ConflictRules {
+    /// Apply a rule written as `POLICY` or `FILE=POLICY`.
+    pub fn add_rule(&mut self, rule: &str) -> Result<(), String> {
+        match rule.split_once('=') {
+            Some((file, policy)) => {
+                let policy = policy.parse()?;
+                self.files.retain(|(name, _)| name != file);
+                self.files.push((file.to_string(), policy));
+            }
+            None => self.default = rule.parse()?,
+        }
+        Ok(())
     }
-    Ok(())
+
+    pub fn policy_for(&self, file_name: &str) -> ConflictPolicy {
+        self.files
+            .iter()
+            .find(|(name, _)| name == file_name)
+            .map_or(self.default, |(_, policy)| *policy)
+    }
+}
+
+/// How a write went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Written {
+    Created,
+    Overwritten,
+    /// The previous file was moved to this path first.
+    BackedUp(PathBuf),
+    /// The file existed and was left alone.
+    Kept,
 }
 
-pub fn create_dockerfile(dir: &str, verbose: bool) -> Result<(), std::io::Error> {
-    let content = docker_content();
-    let path = Path::new(dir).join("Dockerfile");
-    fs::write(path, content)?;
+/// Write `contents` to `path`, resolving an existing file with `policy`.
+pub fn write_file(
+    path: &Path,
+    contents: &str,
+    policy: ConflictPolicy,
+) -> Result<Written, DrakoError> {
+    let written = if !path.exists() {
+        Written::Created
+    } else {
+        match policy {
+            ConflictPolicy::Skip => return Ok(Written::Kept),
+            ConflictPolicy::Overwrite => Written::Overwritten,
+            ConflictPolicy::Backup => {
+                let backup = backup_path(path);
+                fs::rename(path, &backup).map_err(|e| DrakoError::io(path, e))?;
+                Written::BackedUp(backup)
+            }
+            ConflictPolicy::Prompt => match confirm_overwrite(path) {
+                Some(true) => Written::Overwritten,
+                Some(false) => return Ok(Written::Kept),
+                None => return Err(DrakoError::Conflict(path.to_path_buf())),
+            },
+            ConflictPolicy::Fail => return Err(DrakoError::Conflict(path.to_path_buf())),
+        }
+    };
+
+    fs::write(path, contents).map_err(|e| DrakoError::io(path, e))?;
+    Ok(written)
+}
+
+/// Write a template to `path`, resolving an existing file with `policy`.
+pub fn write_template(
+    path: &Path,
+    template: Template,
+    title: Option<&str>,
+    policy: ConflictPolicy,
+) -> Result<Written, DrakoError> {
+    write_file(path, &template.content(title), policy).map_err(|e| match e {
+        DrakoError::Io { path, message } => DrakoError::Template { path, message },
+        other => other,
+    })
+}
+
+/// `<path>.bak`, or `<path>.bak.N` if that is taken too.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let mut backup = PathBuf::from(&name);
+    let mut n = 1;
+    while backup.exists() {
+        backup = PathBuf::from(format!("{}.{}", name.to_string_lossy(), n));
+        n += 1;
+    }
+    backup
+}
 
-    if verbose {
-        success("Created Dockerfile in", Some(dir));
+/// Ask whether to overwrite `path`, or `None` if there is no terminal to ask on.
+fn confirm_overwrite(path: &Path) -> Option<bool> {
+    if !io::stdin().is_terminal() {
+        return None;
     }
-    Ok(())
+    eprint!("Overwrite {}? [y/N] ", path.display());
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok()?;
+    Some(matches!(answer.trim(), "y" | "Y" | "yes"))
 }
 
-pub fn readme_content() -> String {
-    format!(
-        "# Project Title\n\n\
-        Simple overview of use/purpose.\n\n\
+pub fn readme_content(title: &str) -> String {
+    format!("# {}\n\n", title)
+        + "Simple overview of use/purpose.\n\n\
         ## Description\n\n\
         An in-depth paragraph about your project and overview of use.\n\n\
         ## Getting Started\n\n\
@@ -72,7 +233,6 @@ pub fn readme_content() -> String {
         This project is licensed under the [NAME HERE] License - see the LICENSE.md file for details\n\n\
         ## Acknowledgments\n\
         https://twitter.com/dompizzie\n"
-    )
 }
 
 pub fn docker_content() -> String {
@@ -106,8 +266,7 @@ CMD ["echo", "Container is running, customize CMD as needed!"]
 }
 
 pub fn mit_content() -> String {
-    format!(
-        "MIT License\n\n\
+    "MIT License\n\n\
         Copyright (c) [YEAR] [YOUR NAME]\n\n\
         Permission is hereby granted, free of charge, to any person obtaining a copy\n\
         of this software and associated documentation files (the \"Software\"), to deal\n\
@@ -124,5 +283,5 @@ pub fn mit_content() -> String {
         LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,\n\
         OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE\n\
         SOFTWARE.\n"
-    )
+        .to_string()
 }
@@ -0,0 +1,434 @@
+use crate::files::Template;
+
+/// Whether an option stands alone or consumes a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    None,
+    Value(&'static str),
+}
+
+/// What happens when an option is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Run a shell command inside the directory. `{dir}` is replaced with the directory.
+    Run(&'static str),
+    /// Write a template file into the directory.
+    Write(Template),
+    /// Set directory permissions from the option's value.
+    Mode,
+    /// Set the permissions of parent directories drako creates.
+    ParentsMode,
+    /// Give created directories and files to a user and/or group.
+    Owner,
+    /// Give created directories and files to a group.
+    Group,
+    /// Set an extended attribute on created directories.
+    Xattr,
+    /// Set the extended attributes on generated files too.
+    XattrFiles,
+    /// Set a directory up for a group to work in together.
+    Shared,
+    /// Use directories that already exist instead of failing.
+    ExistOk,
+    /// Set the conflict policy for existing files.
+    Conflict,
+    /// Print the plan instead of carrying it out.
+    DryRun,
+    /// Build each project in a staging directory and move it into place.
+    Atomic,
+    /// Choose between text and JSON output.
+    Output,
+    /// Take directory arguments literally, without brace expansion.
+    NoExpand,
+    /// Create the layout described by a tree file inside each directory.
+    FromTree,
+    /// Copy the directory hierarchy of an existing directory.
+    Like,
+    /// Copy modes along with `--like`.
+    CopyModes,
+    /// Refuse names that won't work on every system.
+    Portable,
+    /// Turn names into lowercase, hyphenated directory names.
+    Slug,
+    /// Convert names to the case given as the option's value.
+    Case,
+    /// Put a placeholder file in leaf directories that end up empty.
+    Keep,
+    /// Choose the placeholder file's name.
+    KeepName,
+    /// Print a shell completion script.
+    Completion,
+    /// Print the man page.
+    Man,
+    Verbose,
+    Help,
+    Version,
+}
+
+/// One entry of the option table.
+#[derive(Debug)]
+pub struct FlagSpec {
+    pub long: &'static str,
+    pub short: &'static [&'static str],
+    /// Long names that are still accepted but not advertised.
+    pub aliases: &'static [&'static str],
+    pub arg: ArgKind,
+    pub help: &'static str,
+    pub action: Action,
+}
+
+impl FlagSpec {
+    /// True for options that set something up inside a directory.
+    pub fn is_project_flag(&self) -> bool {
+        matches!(self.action, Action::Run(_) | Action::Write(_))
+    }
+
+    pub fn matches(&self, arg: &str) -> bool {
+        if let Some(name) = arg.strip_prefix("--") {
+            return self.long == name || self.aliases.contains(&name);
+        }
+        match arg.strip_prefix('-') {
+            Some(name) => self.short.contains(&name),
+            None => false,
+        }
+    }
+}
+
+/// Every option drako understands. Parsing, flag processing, help output
+/// and the README table are all derived from this list.
+pub const FLAGS: &[FlagSpec] = &[
+    FlagSpec {
+        long: "git",
+        short: &["g"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Git repository.",
+        action: Action::Run("git init"),
+    },
+    FlagSpec {
+        long: "readme",
+        short: &["r"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Generate a template README.md file.",
+        action: Action::Write(Template::Readme),
+    },
+    FlagSpec {
+        long: "license",
+        short: &["l"],
+        aliases: &["mit"],
+        arg: ArgKind::None,
+        help: "Generate a template MIT License file.",
+        action: Action::Write(Template::License),
+    },
+    FlagSpec {
+        long: "docker",
+        short: &["do"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Generate a template Docker file.",
+        action: Action::Write(Template::Dockerfile),
+    },
+    FlagSpec {
+        long: "go",
+        short: &["go"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Go project.",
+        action: Action::Run("go mod init {dir}"),
+    },
+    FlagSpec {
+        long: "cargo",
+        short: &["c"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Rust Cargo project.",
+        action: Action::Run("cargo init"),
+    },
+    FlagSpec {
+        long: "npm",
+        short: &["n"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize an npm project (package.json).",
+        action: Action::Run("npm init -y"),
+    },
+    FlagSpec {
+        long: "bun",
+        short: &["b"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Bun project.",
+        action: Action::Run("bun init"),
+    },
+    FlagSpec {
+        long: "yarn",
+        short: &["y"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Yarn project.",
+        action: Action::Run("yarn init -y"),
+    },
+    FlagSpec {
+        long: "pnpm",
+        short: &["p"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a pnpm project.",
+        action: Action::Run("pnpm init"),
+    },
+    FlagSpec {
+        long: "deno",
+        short: &["d"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Initialize a Deno project (deno.json).",
+        action: Action::Run("deno init"),
+    },
+    FlagSpec {
+        long: "mode",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("MODE"),
+        help: "Set directory permissions: octal (-755, -2775) or symbolic (g=rxs).",
+        action: Action::Mode,
+    },
+    FlagSpec {
+        long: "parents-mode",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("MODE"),
+        help: "Set permissions of missing parent directories drako creates.",
+        action: Action::ParentsMode,
+    },
+    FlagSpec {
+        long: "owner",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("USER[:GROUP]"),
+        help: "Give created directories and files to USER (and GROUP).",
+        action: Action::Owner,
+    },
+    FlagSpec {
+        long: "group",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("GROUP"),
+        help: "Give created directories and files to GROUP.",
+        action: Action::Group,
+    },
+    FlagSpec {
+        long: "shared",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("GROUP"),
+        help: "Share with GROUP: group-writable, setgid, with default ACLs.",
+        action: Action::Shared,
+    },
+    FlagSpec {
+        long: "xattr",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("KEY=VALUE"),
+        help: "Set an extended attribute on created directories (repeatable).",
+        action: Action::Xattr,
+    },
+    FlagSpec {
+        long: "xattr-files",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "With --xattr, set the attributes on generated files too.",
+        action: Action::XattrFiles,
+    },
+    FlagSpec {
+        long: "from-tree",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("FILE"),
+        help: "Create the directories and files listed in a tree file.",
+        action: Action::FromTree,
+    },
+    FlagSpec {
+        long: "like",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("DIR"),
+        help: "Recreate the directory layout of DIR, skipping what its .gitignore ignores.",
+        action: Action::Like,
+    },
+    FlagSpec {
+        long: "copy-modes",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "With --like, copy each directory's permissions too.",
+        action: Action::CopyModes,
+    },
+    FlagSpec {
+        long: "keep",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Put a .gitkeep in every leaf directory that is still empty at the end.",
+        action: Action::Keep,
+    },
+    FlagSpec {
+        long: "keep-name",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("FILE"),
+        help: "Like --keep, but name the placeholder FILE instead of .gitkeep.",
+        action: Action::KeepName,
+    },
+    FlagSpec {
+        long: "no-expand",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Take directory names literally: no {a,b}, {1..3}, %Y, {user} or trees.",
+        action: Action::NoExpand,
+    },
+    FlagSpec {
+        long: "slug",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Create \"My New Service\" as my-new-service (same as --case kebab).",
+        action: Action::Slug,
+    },
+    FlagSpec {
+        long: "case",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("CASE"),
+        help: "Convert directory names to kebab, snake or camel case.",
+        action: Action::Case,
+    },
+    FlagSpec {
+        long: "portable",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Refuse names that break on Windows, macOS or outside POSIX portable characters.",
+        action: Action::Portable,
+    },
+    FlagSpec {
+        long: "exist-ok",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Set up directories that already exist instead of failing.",
+        action: Action::ExistOk,
+    },
+    FlagSpec {
+        long: "conflict",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("POLICY"),
+        help: "Existing files: skip, overwrite, backup, prompt or fail (default).",
+        action: Action::Conflict,
+    },
+    FlagSpec {
+        long: "atomic",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "All or nothing: remove the new directory if any step fails.",
+        action: Action::Atomic,
+    },
+    FlagSpec {
+        long: "dry-run",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Print what would be done without touching the disk.",
+        action: Action::DryRun,
+    },
+    FlagSpec {
+        long: "output",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("FORMAT"),
+        help: "Print results as text (default) or json, one record per line.",
+        action: Action::Output,
+    },
+    FlagSpec {
+        long: "completion",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::Value("SHELL"),
+        help: "Print a completion script (bash, zsh, fish, powershell).",
+        action: Action::Completion,
+    },
+    FlagSpec {
+        long: "verbose",
+        short: &["v"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Show detailed output from commands.",
+        action: Action::Verbose,
+    },
+    FlagSpec {
+        long: "help",
+        short: &["h"],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Display this help message.",
+        action: Action::Help,
+    },
+    FlagSpec {
+        long: "man",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Print the man page (roff format).",
+        action: Action::Man,
+    },
+    FlagSpec {
+        long: "version",
+        short: &[],
+        aliases: &[],
+        arg: ArgKind::None,
+        help: "Display version.",
+        action: Action::Version,
+    },
+];
+
+/// Shorthand for `--mode`: the octal digits written directly after a dash.
+pub const MODE_SHORTHAND: &str = "-###";
+
+/// Commands run by project flags that name the package they create after
+/// the directory unless told otherwise, and the option that tells them.
+/// Used when `--slug` or `--case` made the directory name from another one.
+pub const NAME_OPTIONS: &[(&str, &str)] = &[("cargo init", "--name")];
+
+/// Look up an option by its spelling on the command line (`--git`, `-g`).
+pub fn lookup(arg: &str) -> Option<&'static FlagSpec> {
+    FLAGS.iter().find(|spec| spec.matches(arg))
+}
+
+/// Find an option by name as written in `drako help <topic>`: with or
+/// without dashes, long or short.
+pub fn find(topic: &str) -> Option<&'static FlagSpec> {
+    if topic.starts_with('-') {
+        return lookup(topic);
+    }
+    lookup(&format!("--{}", topic)).or_else(|| lookup(&format!("-{}", topic)))
+}
+
+/// Short spellings of an option, dash included.
+pub fn short_names(spec: &FlagSpec) -> Vec<String> {
+    if spec.action == Action::Mode {
+        return vec![MODE_SHORTHAND.to_string()];
+    }
+    spec.short.iter().map(|s| format!("-{}", s)).collect()
+}
+
+/// Long spelling of an option together with its value placeholder, if any.
+pub fn long_display(spec: &FlagSpec) -> String {
+    match spec.arg {
+        ArgKind::None => format!("--{}", spec.long),
+        ArgKind::Value(name) => format!("--{} {}", spec.long, name),
+    }
+}
@@ -0,0 +1,409 @@
+//! The journal of what each run created, for `drako undo`.
+//!
+//! Every run that creates something leaves one file in
+//! `$XDG_STATE_HOME/drako/journal/` (usually `~/.local/state/drako/journal/`),
+//! named after the run's id, `<seconds>-<pid>`. Each line is one
+//! tab-separated record: `time`, `cwd` and `args` describe the run, then come
+//! `dir <path>`, `file <size> <mtime> <path> [<backup>]` and
+//! `command <dir> <command>`, parents before what is inside them. Paths are
+//! absolute.
+//!
+//! Files are remembered with their size and modification time when the run
+//! ended, so undo can tell whether they have been changed since. Only the
+//! newest [`MAX_RUNS`] runs are kept.
+
+use crate::error::DrakoError;
+use crate::plan::{Outcome, Report, Step};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many runs the journal remembers. Saving a run forgets the oldest
+/// ones beyond that.
+pub const MAX_RUNS: usize = 100;
+
+/// Something a run created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Dir(PathBuf),
+    File {
+        path: PathBuf,
+        size: u64,
+        /// Modification time in nanoseconds since the epoch.
+        modified: u128,
+        /// Where the file that was in the way was moved, to put it back.
+        backup: Option<PathBuf>,
+    },
+    /// A command run in a directory that already existed. What it did
+    /// there is not known, so it can't be undone.
+    Command {
+        dir: PathBuf,
+        command: String,
+    },
+}
+
+impl Item {
+    pub fn path(&self) -> &Path {
+        match self {
+            Item::Dir(path) | Item::File { path, .. } => path,
+            Item::Command { dir, .. } => dir,
+        }
+    }
+}
+
+/// One drako invocation and what it created.
+#[derive(Debug, Clone, Default)]
+pub struct Run {
+    pub id: String,
+    /// Seconds since the epoch.
+    pub time: i64,
+    pub cwd: PathBuf,
+    /// The command line, after `drako`.
+    pub args: String,
+    pub items: Vec<Item>,
+}
+
+/// What `drako undo` did with a run.
+#[derive(Debug, Default)]
+pub struct Undone {
+    pub removed: Vec<PathBuf>,
+    /// Files put back from the backups drako made of them.
+    pub restored: Vec<PathBuf>,
+    /// Items left alone, and why.
+    pub kept: Vec<(Item, String)>,
+    /// Commands whose effects were not undone.
+    pub commands: Vec<(PathBuf, String)>,
+}
+
+/// `$XDG_STATE_HOME/drako/journal`, else `~/.local/state/drako/journal`.
+pub fn journal_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".local/state"),
+    };
+    Some(base.join("drako").join("journal"))
+}
+
+impl Run {
+    /// A new, empty run for the command line `args`.
+    pub fn start(args: &[String]) -> Run {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Run {
+            id: format!("{}-{}", time, std::process::id()),
+            time,
+            cwd: env::current_dir().unwrap_or_default(),
+            args: args.join(" "),
+            items: Vec::new(),
+        }
+    }
+
+    /// Remember what an executed project created. `parents` are the parents
+    /// of its directory that were missing before it ran, outermost first.
+    pub fn add_project(&mut self, report: &Report, parents: &[PathBuf]) {
+        let mut created_dirs: Vec<PathBuf> = report
+            .results
+            .iter()
+            .filter_map(|result| match (&result.step, &result.outcome) {
+                (Step::CreateDir { path }, Outcome::Done) => Some(self.cwd.join(path)),
+                _ => None,
+            })
+            .filter(|path| path.is_dir())
+            .collect();
+        created_dirs.sort();
+        created_dirs.dedup_by(|inner, outer| inner.starts_with(outer));
+        let root_created = report.results.first().is_some_and(|result| {
+            matches!(result.step, Step::CreateDir { .. }) && result.outcome == Outcome::Done
+        });
+        if root_created {
+            for parent in parents {
+                let parent = self.cwd.join(parent);
+                if parent.is_dir() {
+                    self.items.push(Item::Dir(parent));
+                }
+            }
+        }
+        for dir in &created_dirs {
+            walk(dir, &mut self.items);
+        }
+
+        let covered = |path: &Path| created_dirs.iter().any(|dir| path.starts_with(dir));
+        for result in &report.results {
+            match (&result.step, &result.outcome) {
+                (
+                    Step::WriteFile { path, .. }
+                    | Step::CreateFile { path, .. }
+                    | Step::KeepFile { path },
+                    Outcome::Done | Outcome::BackedUp(_),
+                ) => {
+                    let path = self.cwd.join(path);
+                    let backup = match &result.outcome {
+                        Outcome::BackedUp(backup) => Some(self.cwd.join(backup)),
+                        _ => None,
+                    };
+                    if !covered(&path) {
+                        self.items.extend(file_item(&path, backup));
+                    }
+                }
+                (Step::RunCommand { dir, command, .. }, Outcome::Ran(_) | Outcome::Failed(_)) => {
+                    let dir = self.cwd.join(dir);
+                    if !covered(&dir) {
+                        self.items.push(Item::Command {
+                            dir,
+                            command: command.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Write the run to the journal, unless it created nothing.
+    pub fn save(&self) -> Result<(), DrakoError> {
+        let dir = journal_dir().ok_or_else(|| {
+            DrakoError::Usage("Can't find the journal: HOME is not set".to_string())
+        })?;
+        let path = dir.join(&self.id);
+        if self.items.is_empty() {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(DrakoError::io(&path, e)),
+                _ => Ok(()),
+            };
+        }
+        fs::create_dir_all(&dir).map_err(|e| DrakoError::io(&dir, e))?;
+        fs::write(&path, self.to_text()).map_err(|e| DrakoError::io(&path, e))?;
+        prune(&dir)
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("time\t{}", self.time),
+            format!("cwd\t{}", escape(&self.cwd.to_string_lossy())),
+            format!("args\t{}", escape(&self.args)),
+        ];
+        for item in &self.items {
+            lines.push(match item {
+                Item::Dir(path) => format!("dir\t{}", escape_path(path)),
+                Item::File {
+                    path,
+                    size,
+                    modified,
+                    backup,
+                } => {
+                    let mut line = format!("file\t{}\t{}\t{}", size, modified, escape_path(path));
+                    if let Some(backup) = backup {
+                        line.push_str(&format!("\t{}", escape_path(backup)));
+                    }
+                    line
+                }
+                Item::Command { dir, command } => {
+                    format!("command\t{}\t{}", escape_path(dir), escape(command))
+                }
+            });
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn parse(id: &str, text: &str) -> Option<Run> {
+        let mut run = Run {
+            id: id.to_string(),
+            ..Run::default()
+        };
+        for line in text.lines() {
+            let fields: Vec<String> = line.split('\t').map(unescape).collect();
+            let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+            match fields[..] {
+                ["time", time] => run.time = time.parse().ok()?,
+                ["cwd", cwd] => run.cwd = PathBuf::from(cwd),
+                ["args", args] => run.args = args.to_string(),
+                ["dir", path] => run.items.push(Item::Dir(PathBuf::from(path))),
+                ["file", size, modified, path, ref backup @ ..] if backup.len() <= 1 => {
+                    run.items.push(Item::File {
+                        path: PathBuf::from(path),
+                        size: size.parse().ok()?,
+                        modified: modified.parse().ok()?,
+                        backup: backup.first().map(PathBuf::from),
+                    })
+                }
+                ["command", dir, command] => run.items.push(Item::Command {
+                    dir: PathBuf::from(dir),
+                    command: command.to_string(),
+                }),
+                _ => return None,
+            }
+        }
+        Some(run)
+    }
+}
+
+/// Forget the oldest runs in the journal `dir` beyond [`MAX_RUNS`]. Runs are
+/// ordered by their ids, which start with the time they were made.
+fn prune(dir: &Path) -> Result<(), DrakoError> {
+    let entries = fs::read_dir(dir).map_err(|e| DrakoError::io(dir, e))?;
+    let mut ids: Vec<(i64, String)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let id = entry.file_name().into_string().ok()?;
+            let time = id.split('-').next()?.parse().ok()?;
+            Some((time, id))
+        })
+        .collect();
+    ids.sort();
+    let excess = ids.len().saturating_sub(MAX_RUNS);
+    for (_, id) in &ids[..excess] {
+        let path = dir.join(id);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(DrakoError::io(&path, e)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Every run in the journal, oldest first. Unreadable entries are left out.
+pub fn runs() -> Result<Vec<Run>, DrakoError> {
+    let Some(dir) = journal_dir() else {
+        return Ok(Vec::new());
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(DrakoError::io(&dir, e)),
+    };
+    let mut runs: Vec<Run> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().into_owned();
+            let text = fs::read_to_string(entry.path()).ok()?;
+            Run::parse(&id, &text)
+        })
+        .collect();
+    runs.sort_by(|a, b| (a.time, &a.id).cmp(&(b.time, &b.id)));
+    Ok(runs)
+}
+
+/// Remove what `run` created, newest first, leaving alone anything that
+/// changed since or holds things drako didn't create.
+pub fn undo(run: &Run) -> Undone {
+    let mut undone = Undone::default();
+    for item in run.items.iter().rev() {
+        let metadata = match fs::symlink_metadata(item.path()) {
+            Ok(metadata) => metadata,
+            // Already gone.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                undone.kept.push((item.clone(), e.to_string()));
+                continue;
+            }
+        };
+        let kept = match item {
+            Item::Command { dir, command } => {
+                undone.commands.push((dir.clone(), command.clone()));
+                continue;
+            }
+            Item::Dir(_) if !metadata.is_dir() => Some("it is no longer a directory".to_string()),
+            Item::Dir(path) => match fs::remove_dir(path) {
+                Ok(()) => None,
+                Err(e) if e.kind() == io::ErrorKind::DirectoryNotEmpty => {
+                    Some("it holds files drako didn't create".to_string())
+                }
+                Err(e) => Some(e.to_string()),
+            },
+            Item::File {
+                path,
+                size,
+                modified,
+                backup,
+            } => {
+                if metadata.is_dir() || metadata.len() != *size || mtime(&metadata) != *modified {
+                    Some("it was changed after drako created it".to_string())
+                } else if let Err(e) = fs::remove_file(path) {
+                    Some(e.to_string())
+                } else {
+                    if let Some(backup) = backup.as_ref().filter(|b| b.exists()) {
+                        if fs::rename(backup, path).is_ok() {
+                            undone.restored.push(path.clone());
+                            continue;
+                        }
+                    }
+                    None
+                }
+            }
+        };
+        match kept {
+            Some(reason) => undone.kept.push((item.clone(), reason)),
+            None => undone.removed.push(item.path().to_path_buf()),
+        }
+    }
+    undone
+}
+
+/// Record `dir` and everything in it, parents first. Symlinks are recorded
+/// as files, not followed.
+fn walk(dir: &Path, items: &mut Vec<Item>) {
+    items.push(Item::Dir(dir.to_path_buf()));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<_> = entries.filter_map(Result::ok).collect();
+    children.sort_by_key(|child| child.file_name());
+    for child in children {
+        let path = child.path();
+        match child.file_type() {
+            Ok(file_type) if file_type.is_dir() => walk(&path, items),
+            Ok(_) => items.extend(file_item(&path, None)),
+            Err(_) => {}
+        }
+    }
+}
+
+fn file_item(path: &Path, backup: Option<PathBuf>) -> Option<Item> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    Some(Item::File {
+        path: path.to_path_buf(),
+        size: metadata.len(),
+        modified: mtime(&metadata),
+        backup,
+    })
+}
+
+fn mtime(metadata: &fs::Metadata) -> u128 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos())
+}
+
+fn escape_path(path: &Path) -> String {
+    escape(&path.to_string_lossy())
+}
+
+/// Backslash-escape the characters that separate fields and records.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('t')) => result.push('\t'),
+            ('\\', Some('n')) => result.push('\n'),
+            ('\\', Some('\\')) => result.push('\\'),
+            _ => {
+                result.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    result
+}
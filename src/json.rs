@@ -0,0 +1,194 @@
+use crate::error::DrakoError;
+use crate::plan::{Outcome, Reporter, Step, StepResult};
+use crate::utils::COMMAND_NOT_FOUND;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// How results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Colored messages for humans.
+    #[default]
+    Text,
+    /// One JSON object per step on stdout.
+    Json,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 2] = [OutputFormat::Text, OutputFormat::Json];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OutputFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| format!("Unknown output format: {} (expected text or json)", s))
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A JSON value, as much of it as drako needs.
+enum Value {
+    Str(String),
+    Int(i64),
+}
+
+/// Quote `text` as a JSON string.
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// An object whose keys keep the order they were added in.
+#[derive(Default)]
+struct Record {
+    fields: Vec<(&'static str, Value)>,
+}
+
+impl Record {
+    fn str(&mut self, key: &'static str, value: impl Into<String>) -> &mut Self {
+        self.fields.push((key, Value::Str(value.into())));
+        self
+    }
+
+    fn path(&mut self, key: &'static str, path: &Path) -> &mut Self {
+        self.str(key, path.to_string_lossy())
+    }
+
+    fn int(&mut self, key: &'static str, value: i64) -> &mut Self {
+        self.fields.push((key, Value::Int(value)));
+        self
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match value {
+                Value::Str(s) => write!(f, "{}:{}", quote(key), quote(s))?,
+                Value::Int(n) => write!(f, "{}:{}", quote(key), n)?,
+            }
+        }
+        f.write_str("}")
+    }
+}
+
+/// The JSON record for one finished step.
+pub fn record(result: &StepResult) -> String {
+    let mut record = Record::default();
+    match &result.step {
+        Step::CreateDir { path } => record.str("action", "create_dir").path("path", path),
+        Step::UseDir { path } => record.str("action", "use_dir").path("path", path),
+        Step::SetPermissions { path, mode } => record
+            .str("action", "set_permissions")
+            .path("path", path)
+            .str("mode", mode.to_string()),
+        Step::SetDefaultAcl { path } => record.str("action", "set_default_acl").path("path", path),
+        Step::SetXattr { path, name, value } => record
+            .str("action", "set_xattr")
+            .path("path", path)
+            .str("name", name.as_str())
+            .str("value", value.as_str()),
+        Step::Commit { staging, path } => record
+            .str("action", "commit")
+            .path("path", path)
+            .path("staging", staging),
+        Step::RollBack { staging, .. } => record.str("action", "roll_back").path("path", staging),
+        Step::SetOwner { path, owner } => record
+            .str("action", "set_owner")
+            .path("path", path)
+            .str("owner", owner.to_string()),
+        Step::WriteFile { path, .. } => record.str("action", "write_file").path("path", path),
+        Step::CreateFile { path, .. } => record.str("action", "create_file").path("path", path),
+        Step::KeepFile { path } => record.str("action", "keep_file").path("path", path),
+        Step::RunCommand { dir, command, .. } => record
+            .str("action", "run_command")
+            .path("dir", dir)
+            .str("command", command.as_str()),
+    };
+
+    match &result.outcome {
+        Outcome::Done => {
+            record.str("status", "done");
+        }
+        Outcome::Ran(stderr) => {
+            record
+                .str("status", "done")
+                .int("exit_status", 0)
+                .str("stderr", stderr.as_str());
+        }
+        Outcome::Planned => {
+            record.str("status", "planned");
+        }
+        Outcome::BackedUp(backup) => {
+            record.str("status", "done").path("backup", backup);
+        }
+        Outcome::Skipped(reason) => {
+            record
+                .str("status", "skipped")
+                .str("reason", reason.as_str());
+        }
+        Outcome::Failed(e) => {
+            record
+                .str("status", "failed")
+                .str("reason", e.to_string())
+                .int("exit_code", e.exit_code().into());
+            match e {
+                DrakoError::CommandFailed { status, stderr, .. } => {
+                    if let Some(status) = status {
+                        record.int("exit_status", (*status).into());
+                    }
+                    record.str("stderr", stderr.as_str());
+                }
+                DrakoError::ToolMissing(_) => {
+                    record.int("exit_status", COMMAND_NOT_FOUND.into());
+                }
+                _ => {}
+            }
+        }
+    }
+    record.to_string()
+}
+
+/// Prints one JSON record per step on stdout (JSON Lines).
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn step_finished(&mut self, result: &StepResult) {
+        let _ = writeln!(io::stdout(), "{}", record(result));
+    }
+}
@@ -0,0 +1,151 @@
+//! Tree spec files for `--from-tree`.
+//!
+//! Each line names one directory or empty file, indented under its parent;
+//! `#` starts a comment:
+//!
+//! ```text
+//! # service layout
+//! src/
+//!   bin/          -750
+//!   main.rs
+//! docs/           --readme
+//! scripts/        mode=700
+//!   "run tests.sh"  mode=755
+//! ```
+//!
+//! A name ending in `/`, or with lines indented below it, is a directory;
+//! anything else is an empty file. After the name come an optional mode
+//! (`-750`, `mode=750` or `mode=u=rwx,g=rx`) and project flags such as
+//! `--readme`, which only directories may have.
+
+use crate::error::DrakoError;
+use crate::flags;
+use crate::plan::{Entry, EntryKind};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Read the entries described by the tree file at `path`.
+pub fn load(path: &Path) -> Result<Vec<Entry>, DrakoError> {
+    let text = fs::read_to_string(path).map_err(|e| DrakoError::io(path, e))?;
+    parse(&text).map_err(|(line, message)| DrakoError::Config {
+        path: path.to_path_buf(),
+        line,
+        message,
+    })
+}
+
+/// Parse tree file text; errors carry a 1-based line number.
+pub fn parse(text: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut entries: Vec<Entry> = Vec::new();
+    // Open parents, innermost last: (indent, entry index, indent of their children).
+    let mut parents: Vec<(usize, usize, Option<usize>)> = Vec::new();
+    let mut top_indent = None;
+    let mut lines = Vec::new();
+
+    for (number, raw) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = strip_comment(raw).trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let body = line.trim_start_matches(' ');
+        if body.starts_with('\t') {
+            return Err((number, "indent with spaces, not tabs".to_string()));
+        }
+        let indent = line.len() - body.len();
+
+        while parents.last().is_some_and(|(i, _, _)| *i >= indent) {
+            parents.pop();
+        }
+        let level = match parents.last_mut() {
+            Some((_, _, children)) => children,
+            None => &mut top_indent,
+        };
+        if level.is_some_and(|i| i != indent) {
+            return Err((number, "indent does not match any outer level".to_string()));
+        }
+        *level = Some(indent);
+
+        let mut entry = parse_line(body).map_err(|message| (number, message))?;
+        if let Some(&(_, parent, _)) = parents.last() {
+            let parent = &mut entries[parent];
+            parent.kind = EntryKind::Dir;
+            entry.path = parent.path.join(&entry.path);
+        }
+        parents.push((indent, entries.len(), None));
+        entries.push(entry);
+        lines.push(number);
+    }
+
+    for (entry, number) in entries.iter().zip(lines) {
+        if entry.kind == EntryKind::File && !entry.flags.is_empty() {
+            return Err((number, "only directories can have flags".to_string()));
+        }
+    }
+    Ok(entries)
+}
+
+/// Everything before an unquoted `#`.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_line(line: &str) -> Result<Entry, String> {
+    let (name, rest) = match line.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let end = line[1..]
+                .find(quote)
+                .ok_or_else(|| "quote is never closed".to_string())?;
+            (&line[1..end + 1], &line[end + 2..])
+        }
+        _ => line.split_once(' ').unwrap_or((line, "")),
+    };
+
+    let kind = if name.ends_with('/') {
+        EntryKind::Dir
+    } else {
+        EntryKind::File
+    };
+    let path = PathBuf::from(name.trim_end_matches('/'));
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("`{}` must be a relative path without `..`", name));
+    }
+
+    let mut entry = Entry {
+        path,
+        kind,
+        mode: None,
+        flags: Vec::new(),
+    };
+    for word in rest.split_whitespace() {
+        let mode = word.strip_prefix("mode=").or_else(|| {
+            word.strip_prefix('-')
+                .filter(|m| m.starts_with(|c: char| c.is_ascii_digit()))
+        });
+        if let Some(mode) = mode {
+            entry.mode = Some(
+                mode.parse()
+                    .map_err(|reason| format!("invalid mode `{}`: {}", word, reason))?,
+            );
+            continue;
+        }
+        match flags::lookup(word) {
+            Some(spec) if spec.is_project_flag() => entry.flags.push(spec),
+            _ => return Err(format!("`{}` is not a mode or project flag", word)),
+        }
+    }
+    Ok(entry)
+}
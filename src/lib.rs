@@ -0,0 +1,36 @@
+//! drako creates directories and sets up projects inside them.
+//!
+//! The `drako` binary is a thin front-end over this crate: build a
+//! [`ProjectSpec`], turn it into a [`Plan`] and execute it to get a
+//! [`Report`] describing what happened to every step. [`args::expand_args`]
+//! reads directory arguments the way the command line does, braces, trees
+//! and all, into one [`ProjectSpec`] per directory.
+
+pub mod args;
+pub mod cli;
+pub mod completion;
+pub mod config;
+pub mod docs;
+pub mod error;
+pub mod expand;
+pub mod files;
+pub mod flags;
+pub mod journal;
+pub mod json;
+pub mod layout;
+pub mod messages;
+pub mod mode;
+pub mod naming;
+pub mod owner;
+pub mod placeholders;
+pub mod plan;
+pub mod portable;
+pub mod skeleton;
+pub mod tree;
+pub mod utils;
+pub mod xattr;
+
+pub use error::DrakoError;
+pub use mode::Mode;
+pub use plan::{Entry, EntryKind, Outcome, Plan, ProjectSpec, Report, Reporter, Step, StepResult};
+pub use utils::{create_directory, process_flags, set_permissions};
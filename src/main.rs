@@ -1,78 +1,278 @@
-mod files;
-mod messages;
-mod utils;
-
-use messages::*;
-use utils::*;
+use drako::args::{self, ArgOptions};
+use drako::cli::{self, Command, Options};
+use drako::completion::{self, Shell};
+use drako::config::{self, Config};
+use drako::json::{JsonReporter, OutputFormat};
+use drako::messages::*;
+use drako::plan::missing_parents;
+use drako::{docs, flags, journal, layout, portable, skeleton};
+use drako::{DrakoError, Plan, ProjectSpec, Reporter};
 
+use std::collections::HashSet;
 use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 fn main() {
-    // cli arguments get passed to collection
-    // Separate directory names from flags and permissions
-    let args: Vec<String> = env::args().collect();
-    let mut dirs: Vec<String> = Vec::new();
-    let mut flags: Vec<String> = Vec::new();
-    let mut permissions: Option<u32> = None;
-    let mut verbose = false;
-
-    if args.len() < 2 {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let usage_error = DrakoError::Usage(String::new()).exit_code();
+
+    if args.is_empty() {
         usage();
+        std::process::exit(usage_error);
+    }
+
+    let options = match cli::parse(args.clone()) {
+        Ok(options) => options,
+        Err(e) => {
+            error(&e.to_string(), None);
+            usage();
+            std::process::exit(usage_error);
+        }
+    };
+
+    if options.help {
+        match options.help_topic.as_deref().map(|t| (t, flags::find(t))) {
+            None => help(),
+            Some((_, Some(spec))) => help_topic(spec),
+            Some((topic, None)) => {
+                error("No help topic for", Some(topic));
+                std::process::exit(usage_error);
+            }
+        }
         std::process::exit(0);
     }
 
-    // Check for --help or -h flag
-    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
-        help();
+    if options.man {
+        print_stdout(&docs::man_page());
         std::process::exit(0);
     }
 
-    if args.contains(&"--version".to_string()) {
+    if options.version {
         version();
         std::process::exit(0);
     }
 
-    for arg in args.iter().skip(1) {
-        if !arg.starts_with('-') {
-            dirs.push(arg.clone());
-            continue;
+    if let Some(shell) = &options.completion {
+        match shell.parse::<Shell>() {
+            Ok(shell) => {
+                print_stdout(&completion::generate(shell));
+                std::process::exit(0);
+            }
+            Err(e) => {
+                error(&e, None);
+                std::process::exit(usage_error);
+            }
         }
+    }
+
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            error(&e.to_string(), None);
+            std::process::exit(e.exit_code());
+        }
+    };
 
-        match arg.as_str() {
-            "--verbose" | "-v" => {
-                verbose = true;
+    match options.command {
+        Command::TemplatesList => templates_list(),
+        Command::ConfigShow => config_show(&config),
+        Command::UndoList => match journal::runs() {
+            Ok(runs) => undo_list(&runs),
+            Err(e) => {
+                error(&e.to_string(), None);
+                std::process::exit(e.exit_code());
             }
-            arg_str if arg_str.starts_with("--") => {
-                flags.push(arg.clone());
+        },
+        Command::Undo => std::process::exit(undo(&options)),
+        Command::New | Command::Add => {
+            if options.dirs.is_empty() {
+                error("No directories provided", None);
+                std::process::exit(usage_error);
             }
-            arg_str if arg_str.starts_with('-') && arg_str.len() > 1 => {
-                let perm_str = &arg_str[1..]; // Remove the dash
-                if perm_str.len() <= 3 && perm_str.chars().all(|c| c.is_ascii_digit() && c < '8') {
-                    match u32::from_str_radix(perm_str, 8) {
-                        Ok(perm) => permissions = Some(perm),
-                        Err(_) => flags.push(arg.clone()), // Treat as flag if not valid permission
-                    }
-                } else {
-                    flags.push(arg.clone()); // Single char flags like -g, -r, etc.
-                }
+            if options.atomic && options.command == Command::Add {
+                error(
+                    "--atomic only works for new directories, not drako add",
+                    None,
+                );
+                std::process::exit(usage_error);
             }
-            _ => {
-                flags.push(arg.clone());
+            let mut run = journal::Run::start(&args);
+            let exit_code = run_projects(&options, &config, &mut run);
+            if !options.dry_run {
+                if let Err(e) = run.save() {
+                    warning(
+                        "Could not record the run for drako undo:",
+                        Some(&e.to_string()),
+                    );
+                }
             }
+            std::process::exit(exit_code);
         }
     }
+}
 
-    if dirs.is_empty() {
-        error("No directories provided", None);
-        std::process::exit(1);
+/// Create or set up every directory given, returning the exit code.
+fn run_projects(options: &Options, config: &Config, run: &mut journal::Run) -> i32 {
+    let existing = options.command == Command::Add;
+    let mut flags = options.flags.clone();
+    let mut mode = options.mode.clone();
+    if !existing {
+        // Config defaults only apply to brand-new projects.
+        for spec in &config.flags {
+            if !flags.iter().any(|f| std::ptr::eq(*f, *spec)) {
+                flags.push(spec);
+            }
+        }
+        mode = mode.or(config.mode.clone());
+    }
+    let mut conflicts = config.conflicts.clone();
+    for rule in &options.conflict_rules {
+        // Already validated by the parser.
+        let _ = conflicts.add_rule(rule);
     }
 
-    for dir in &dirs {
-        if create_directory(dir, verbose) {
-            if let Some(mode) = permissions {
-                set_permissions(dir, mode, verbose);
+    let mut exit_code = 0;
+    let mut console = ConsoleReporter {
+        verbose: options.verbose,
+        dry_run: options.dry_run,
+    };
+    let reporter: &mut dyn Reporter = match options.output {
+        OutputFormat::Text => &mut console,
+        OutputFormat::Json => &mut JsonReporter,
+    };
+    let mut layout = match &options.from_tree {
+        Some(path) => match layout::load(Path::new(path)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error(&e.to_string(), None);
+                return e.exit_code();
+            }
+        },
+        None => Vec::new(),
+    };
+    if let Some(source) = &options.like {
+        match skeleton::scan(Path::new(source), options.copy_modes) {
+            Ok(skeleton) => {
+                mode = mode.or(skeleton.mode);
+                layout.extend(skeleton.entries);
             }
-            process_flags(dir, &flags, verbose);
+            Err(e) => {
+                error(&e.to_string(), None);
+                return e.exit_code();
+            }
+        }
+    }
+
+    let base = ProjectSpec {
+        mode,
+        parents_mode: options.parents_mode.clone(),
+        owner: options.owner.clone(),
+        shared: options.shared,
+        xattrs: options.xattrs.clone(),
+        xattr_files: options.xattr_files,
+        keep: options.keep.clone(),
+        flags,
+        existing,
+        exist_ok: options.exist_ok,
+        conflicts,
+        entries: layout,
+        ..ProjectSpec::default()
+    };
+    let arg_options = ArgOptions {
+        no_expand: options.no_expand,
+        case: options.case,
+    };
+    let projects = match args::expand_args(&options.dirs, arg_options, &base) {
+        Ok(projects) => projects,
+        Err(e) => {
+            error(&e.to_string(), None);
+            return e.exit_code();
+        }
+    };
+
+    let problems = portable::check(&portable::new_paths(&projects), options.portable);
+    for problem in &problems {
+        let detail = format!("{} ({})", problem.path.display(), problem.reason);
+        if options.portable {
+            error("Not a portable name:", Some(&detail));
+        } else {
+            warning("Not a portable name:", Some(&detail));
+        }
+    }
+    if options.portable && !problems.is_empty() {
+        return DrakoError::Usage(String::new()).exit_code();
+    }
+
+    // Parents shared by several projects are only listed once in a dry run.
+    let mut planned = HashSet::new();
+    for spec in &projects {
+        let plan = Plan::for_project(spec);
+        let mut parents: Vec<PathBuf> = missing_parents(Path::new(&spec.dir))
+            .map(Path::to_path_buf)
+            .collect();
+        parents.reverse();
+        let report = if options.dry_run {
+            plan.dry_run(reporter, &mut planned)
+        } else if options.atomic {
+            plan.execute_atomically(reporter)
+        } else {
+            plan.execute(reporter)
+        };
+        if !options.dry_run {
+            run.add_project(&report, &parents);
+        }
+        if exit_code == 0 {
+            exit_code = report.exit_code();
         }
     }
+    exit_code
+}
+
+/// `drako undo [RUN]`: remove what a run created, returning the exit code.
+fn undo(options: &Options) -> i32 {
+    let usage_error = DrakoError::Usage(String::new()).exit_code();
+    if options.dirs.len() > 1 {
+        error("drako undo takes at most one run id", None);
+        return usage_error;
+    }
+    let runs = match journal::runs() {
+        Ok(runs) => runs,
+        Err(e) => {
+            error(&e.to_string(), None);
+            return e.exit_code();
+        }
+    };
+    let run = match options.dirs.first() {
+        Some(id) => runs.iter().find(|run| &run.id == id),
+        None => runs.last(),
+    };
+    let Some(run) = run else {
+        match options.dirs.first() {
+            Some(id) => error("No such run in the journal:", Some(id)),
+            None => error("Nothing to undo", None),
+        }
+        return usage_error;
+    };
+
+    let undone = journal::undo(run);
+    undo_report(run, &undone, options.verbose);
+    let remaining = journal::Run {
+        items: undone.kept.iter().map(|(item, _)| item.clone()).collect(),
+        ..run.clone()
+    };
+    if let Err(e) = remaining.save() {
+        error(&e.to_string(), None);
+        return e.exit_code();
+    }
+    match undone.kept.first() {
+        Some((item, _)) => DrakoError::Conflict(item.path().to_path_buf()).exit_code(),
+        None => 0,
+    }
+}
+
+/// Print generated text, tolerating a closed pipe (`drako --man | head`).
+fn print_stdout(text: &str) {
+    let _ = io::stdout().write_all(text.as_bytes());
 }
@@ -1,3 +1,13 @@
+use crate::config::Config;
+use crate::docs;
+use crate::error::DrakoError;
+use crate::flags::{long_display, short_names, Action, FlagSpec, FLAGS};
+use crate::journal::{Run, Undone};
+use crate::mode::Mode;
+use crate::placeholders;
+use crate::plan::{Outcome, Reporter, Step, StepResult};
+use std::fs;
+
 pub const YELLOW: &str = "\x1b[1;33m";
 pub const RED: &str = "\x1b[1;31m";
 pub const GREEN: &str = "\x1b[1;32m";
@@ -12,10 +22,17 @@ pub fn usage() {
     eprintln!("{}Usage: drako new_directory [options]...", YELLOW);
 }
 
+pub fn info(input: &str, arg: Option<&str>) {
+    match arg {
+        Some(arg) => println!("{}{}{} {}", GREEN, input, RESET, arg),
+        None => println!("{}{}{}", GREEN, input, RESET),
+    }
+}
+
 pub fn success(input: &str, arg: Option<&str>) {
     match arg {
-        Some(arg) => eprintln!("{}Success: {} {}", GREEN, input, arg),
-        None => eprintln!("{}Success: {}", YELLOW, input),
+        Some(arg) => println!("{}Success: {} {}", GREEN, input, arg),
+        None => println!("{}Success: {}", YELLOW, input),
     }
 }
 
@@ -34,30 +51,239 @@ pub fn error(input: &str, arg: Option<&str>) {
 }
 
 pub fn help() {
+    let mut options = String::new();
+    for spec in FLAGS {
+        let long = long_display(spec);
+        let names = match short_names(spec).join(", ") {
+            short if short.is_empty() => long,
+            short => format!("{:<10} {}", format!("{},", long), short),
+        };
+        options.push_str(&format!("    {GREEN}{:<22}{RESET}{}\n", names, spec.help));
+    }
+
     let help_message = format!(
         "{YELLOW}Usage:{RESET} drako [directories] [options]...
+       drako new|add [directories] [options]...
+       drako templates list
+       drako config show
+       drako undo [list | run]
+       drako help [option]
 
 {YELLOW}Help:{RESET}  Creates one or more directories with optional project initialization.
     Multiple directories can be specified, and options apply to all of them.
+    {GREEN}add{RESET} applies the same options to directories that already exist.
 
 {YELLOW}Options:{RESET}
-    {GREEN}--git,     -g{RESET}         Initialize a Git repository.
-    {GREEN}--readme,  -r{RESET}         Generate a template README.md file.
-    {GREEN}--license, -l{RESET}         Generate a template MIT License file.
-    {GREEN}--docker,  -do{RESET}        Generate a template Docker file.
-    {GREEN}--go,      -go{RESET}        Initialize a Go project.
-    {GREEN}--cargo,   -c{RESET}         Initialize a Rust Cargo project.
-    {GREEN}--npm,     -n{RESET}         Initialize an npm project (package.json).
-    {GREEN}--bun,     -b{RESET}         Initialize a Bun project.
-    {GREEN}--yarn,    -y{RESET}         Initialize a Yarn project.
-    {GREEN}--pnpm,    -p{RESET}         Initialize a pnpm project.
-    {GREEN}--deno,    -d{RESET}         Initialize a Deno project (deno.json).
-    {GREEN}--verbose, -v{RESET}         Show detailed output from commands.
-    {GREEN}           -### {RESET}      Set directory permissions (octal format, e.g., -700, -755).
-    {GREEN}--help     -h{RESET}         Display this help message.
-    {GREEN}--version    {RESET}         Display version.
-",
+{options}
+Run {GREEN}drako help <option>{RESET} for the commands and files an option produces.",
     );
 
     eprintln!("{}", help_message);
 }
+
+pub fn help_topic(spec: &FlagSpec) {
+    eprintln!("{}", docs::topic(spec));
+}
+
+/// `drako templates list`: every file drako can generate and the flag that does it.
+pub fn templates_list() {
+    for spec in FLAGS {
+        if let Action::Write(template) = spec.action {
+            println!(
+                "{GREEN}{:<10}{RESET} {:<12} --{}",
+                spec.long,
+                template.file_name(),
+                spec.long
+            );
+        }
+    }
+}
+
+/// `drako config show`: where the config lives and what it sets.
+pub fn config_show(config: &Config) {
+    let path = match &config.path {
+        Some(path) if config.loaded => path.display().to_string(),
+        Some(path) => format!("{} (not found)", path.display()),
+        None => "(no config location)".to_string(),
+    };
+    let flags = config
+        .flags
+        .iter()
+        .map(|spec| format!("--{}", spec.long))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mode = config
+        .mode
+        .as_ref()
+        .map(Mode::to_string)
+        .unwrap_or_default();
+    let mut conflict = vec![config.conflicts.default.to_string()];
+    conflict.extend(
+        config
+            .conflicts
+            .files
+            .iter()
+            .map(|(file, policy)| format!("{}={}", file, policy)),
+    );
+
+    println!("{YELLOW}config{RESET} {}", path);
+    println!("{YELLOW}flags{RESET}  {}", flags);
+    println!("{YELLOW}mode{RESET}   {}", mode);
+    println!("{YELLOW}conflict{RESET} {}", conflict.join(" "));
+}
+
+/// `drako undo list`: every run in the journal, newest first.
+pub fn undo_list(runs: &[Run]) {
+    if runs.is_empty() {
+        info("Nothing to undo", None);
+    }
+    for run in runs.iter().rev() {
+        let time = placeholders::format_time("%Y-%m-%d %H:%M", run.time).unwrap_or_default();
+        println!(
+            "{GREEN}{}{RESET}  {}  {}  drako {}",
+            run.id,
+            time,
+            run.cwd.display(),
+            run.args
+        );
+    }
+}
+
+/// What `drako undo` removed and what it left alone.
+pub fn undo_report(run: &Run, undone: &Undone, verbose: bool) {
+    if verbose {
+        for path in &undone.removed {
+            success("Removed", Some(&path.display().to_string()));
+        }
+    }
+    for path in &undone.restored {
+        success("Restored the previous", Some(&path.display().to_string()));
+    }
+    for (dir, command) in &undone.commands {
+        warning(
+            &format!("Not undone: {} in", command),
+            Some(&dir.display().to_string()),
+        );
+    }
+    for (item, reason) in &undone.kept {
+        warning(&format!("Kept {}:", item.path().display()), Some(reason));
+    }
+    success(
+        &format!(
+            "Undid run {}: removed {} of {} paths",
+            run.id,
+            undone.removed.len() + undone.restored.len(),
+            undone.removed.len() + undone.restored.len() + undone.kept.len()
+        ),
+        None,
+    );
+}
+
+/// Prints progress for humans: errors always, everything else with `--verbose`.
+/// With `--dry-run` every step is listed.
+pub struct ConsoleReporter {
+    pub verbose: bool,
+    pub dry_run: bool,
+}
+
+impl Reporter for ConsoleReporter {
+    fn step_started(&mut self, step: &Step) {
+        if !self.verbose {
+            return;
+        }
+        match step {
+            Step::CreateDir { path } => {
+                info("Creating directory", Some(&path.display().to_string()))
+            }
+            Step::UseDir { path } => info("Using directory", Some(&path.display().to_string())),
+            Step::RunCommand { dir, command, .. } => info(
+                &format!("Running {} in", command),
+                Some(&dir.display().to_string()),
+            ),
+            _ => {}
+        }
+    }
+
+    fn step_finished(&mut self, result: &StepResult) {
+        match (&result.outcome, &result.step) {
+            (Outcome::Done, Step::RollBack { staging, .. }) => warning(
+                "Removed the unfinished project",
+                Some(&staging.display().to_string()),
+            ),
+            (Outcome::Done | Outcome::Ran(_), _) if !self.verbose => {}
+            (Outcome::Done, Step::CreateDir { path }) => {
+                let full_path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+                success("Created directory", Some(&full_path.display().to_string()))
+            }
+            (Outcome::Done, Step::UseDir { .. }) => {}
+            (Outcome::Done, Step::SetPermissions { path, mode }) => success(
+                &format!("Set permissions {} on", mode),
+                Some(&path.display().to_string()),
+            ),
+            (Outcome::Done, Step::SetDefaultAcl { path }) => {
+                success("Set default ACL on", Some(&path.display().to_string()))
+            }
+            (Outcome::Done, Step::SetXattr { path, name, value }) => success(
+                &format!("Set {}={} on", name, value),
+                Some(&path.display().to_string()),
+            ),
+            (Outcome::Done, Step::Commit { path, .. }) => success(
+                "Moved the finished project to",
+                Some(&path.display().to_string()),
+            ),
+            (Outcome::Done, Step::SetOwner { path, owner }) => success(
+                &format!("Gave {} to", path.display()),
+                Some(&owner.to_string()),
+            ),
+            (Outcome::Done, Step::WriteFile { path, template, .. }) => {
+                let dir = path.parent().unwrap_or(path);
+                success(
+                    &format!("Created {} in", template.file_name()),
+                    Some(&dir.display().to_string()),
+                )
+            }
+            (Outcome::Done, Step::CreateFile { path, .. } | Step::KeepFile { path }) => {
+                success("Created file", Some(&path.display().to_string()))
+            }
+            (Outcome::Done | Outcome::Ran(_), Step::RunCommand { dir, command, .. }) => success(
+                &format!("Ran {} in", command),
+                Some(&dir.display().to_string()),
+            ),
+            (Outcome::Ran(_), _) => {}
+            (Outcome::Planned, step) => info(&format!("Would {}", step), None),
+            (Outcome::BackedUp(backup), step) if self.dry_run => info(
+                &format!("Would {}, keeping the old file as", step),
+                Some(&backup.display().to_string()),
+            ),
+            (Outcome::BackedUp(backup), Step::WriteFile { path, .. }) if self.verbose => success(
+                &format!("Replaced {}, previous file kept as", path.display()),
+                Some(&backup.display().to_string()),
+            ),
+            (Outcome::BackedUp(_), _) => {}
+            (Outcome::Skipped(reason), Step::SetDefaultAcl { path }) => warning(
+                &format!("{}, so files in it follow the umask:", reason),
+                Some(&path.display().to_string()),
+            ),
+            (Outcome::Skipped(reason), step) if self.verbose || self.dry_run => {
+                info(&format!("Skipped {}:", step), Some(reason))
+            }
+            (Outcome::Skipped(_), _) => {}
+            (Outcome::Failed(e @ DrakoError::AlreadyExists(_)), _) if self.verbose => {
+                info(&e.to_string(), None)
+            }
+            (Outcome::Failed(e @ DrakoError::AlreadyExists(_)), _) => warning(&e.to_string(), None),
+            (Outcome::Failed(DrakoError::PermissionDenied(_)), Step::SetOwner { path, owner }) => {
+                error(
+                    &format!(
+                        "Not permitted to give {} to {} (only root can change the owner, \
+                         and only to a group you belong to)",
+                        path.display(),
+                        owner
+                    ),
+                    None,
+                )
+            }
+            (Outcome::Failed(e), _) => error(&e.to_string(), None),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Permission modes for `--mode`, in the forms `chmod(1)` accepts.
+//!
+//! - Octal, up to four digits: `755`, `2775` for a setgid directory shared by
+//!   a group, `1777` for a sticky one.
+//! - Symbolic, comma-separated clauses such as `u=rwx,g=rxs,o=`. Each clause
+//!   names classes (`u`, `g`, `o`, `a`; none means `a`), then one or more
+//!   operations `+`, `-` or `=` with permissions from `rwxXst`, or a class to
+//!   copy from, as in `g=u`. Unlike `chmod`, the umask is not consulted.
+
+use std::fmt;
+use std::str::FromStr;
+
+const USER: u32 = 0o4700;
+const GROUP: u32 = 0o2070;
+const OTHER: u32 = 0o1007;
+
+/// A mode to give a directory or file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Every bit given outright.
+    Octal(u32),
+    /// Changes to the current mode, kept with the text they came from.
+    Symbolic { spec: String, clauses: Vec<Clause> },
+}
+
+/// One comma-separated part of a symbolic mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    /// Bits of the classes it applies to: `USER`, `GROUP` and/or `OTHER`.
+    who: u32,
+    ops: Vec<(char, Perms)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Perms {
+    /// Any of `rwxXst`.
+    Letters(String),
+    /// The current permissions of another class.
+    Copy(u32),
+}
+
+impl Mode {
+    /// The mode a path with `current` mode ends up with.
+    pub fn apply(&self, current: u32, is_dir: bool) -> u32 {
+        let clauses = match self {
+            Mode::Octal(mode) => return *mode,
+            Mode::Symbolic { clauses, .. } => clauses,
+        };
+        let mut mode = current & 0o7777;
+        for clause in clauses {
+            for (op, perms) in &clause.ops {
+                let bits = perms.bits(mode, is_dir) & clause.who;
+                match op {
+                    '+' => mode |= bits,
+                    '-' => mode &= !bits,
+                    _ => mode = (mode & !clause.who) | bits,
+                }
+            }
+        }
+        mode
+    }
+}
+
+impl Perms {
+    /// The bits these permissions stand for in every class.
+    fn bits(&self, current: u32, is_dir: bool) -> u32 {
+        match self {
+            Perms::Copy(class) => {
+                let shift = match *class {
+                    USER => 6,
+                    GROUP => 3,
+                    _ => 0,
+                };
+                ((current >> shift) & 0o7) * 0o111
+            }
+            Perms::Letters(letters) => letters
+                .chars()
+                .map(|c| match c {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    'x' => 0o111,
+                    'X' if is_dir || current & 0o111 != 0 => 0o111,
+                    's' => 0o6000,
+                    't' => 0o1000,
+                    _ => 0,
+                })
+                .fold(0, |bits, bit| bits | bit),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Mode, String> {
+        if spec.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            if spec.len() > 4 || !spec.chars().all(|c| ('0'..='7').contains(&c)) {
+                return Err("octal modes have up to four digits from 0 to 7".to_string());
+            }
+            return Ok(Mode::Octal(u32::from_str_radix(spec, 8).unwrap_or(0)));
+        }
+        let clauses = spec
+            .split(',')
+            .map(|clause| {
+                parse_clause(clause).ok_or_else(|| format!("`{}` is not a symbolic mode", clause))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Mode::Symbolic {
+            spec: spec.to_string(),
+            clauses,
+        })
+    }
+}
+
+fn parse_clause(clause: &str) -> Option<Clause> {
+    let start = clause.find(['+', '-', '='])?;
+    let mut who = 0;
+    for c in clause[..start].chars() {
+        who |= class(c).or((c == 'a').then_some(USER | GROUP | OTHER))?;
+    }
+    if who == 0 {
+        who = USER | GROUP | OTHER;
+    }
+
+    let mut ops = Vec::new();
+    let mut rest = &clause[start..];
+    while let Some(op) = rest.chars().next() {
+        let end = rest[1..]
+            .find(['+', '-', '='])
+            .map_or(rest.len(), |i| i + 1);
+        let perms = &rest[1..end];
+        let perms = match perms.chars().next().and_then(class) {
+            Some(from) if perms.len() == 1 => Perms::Copy(from),
+            _ if perms.chars().all(|c| "rwxXst".contains(c)) => Perms::Letters(perms.to_string()),
+            _ => return None,
+        };
+        ops.push((op, perms));
+        rest = &rest[end..];
+    }
+    Some(Clause { who, ops })
+}
+
+fn class(c: char) -> Option<u32> {
+    match c {
+        'u' => Some(USER),
+        'g' => Some(GROUP),
+        'o' => Some(OTHER),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Octal(mode) => write!(f, "{:o}", mode),
+            Mode::Symbolic { spec, .. } => write!(f, "{}", spec),
+        }
+    }
+}
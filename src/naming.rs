@@ -0,0 +1,106 @@
+//! Turning typed names into directory names: Unicode NFC normalization and
+//! the `--slug` and `--case` conversions.
+
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
+
+/// How `--case` spells a name made of several words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `my-new-service`, also what `--slug` gives.
+    Kebab,
+    /// `my_new_service`
+    Snake,
+    /// `MyNewService`
+    Camel,
+}
+
+impl Case {
+    pub const ALL: [Case; 3] = [Case::Kebab, Case::Snake, Case::Camel];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Case::Kebab => "kebab",
+            Case::Snake => "snake",
+            Case::Camel => "camel",
+        }
+    }
+
+    /// `name` spelled in this case. A name without any letters or digits is
+    /// returned as it is.
+    pub fn apply(&self, name: &str) -> String {
+        let words = words(name);
+        if words.is_empty() {
+            return name.to_string();
+        }
+        match self {
+            Case::Kebab => words.join("-").to_lowercase(),
+            Case::Snake => words.join("_").to_lowercase(),
+            Case::Camel => words
+                .iter()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    let first = chars.next().into_iter().flat_map(char::to_uppercase);
+                    first.chain(chars.as_str().to_lowercase().chars()).collect()
+                })
+                .collect::<Vec<String>>()
+                .concat(),
+        }
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Case {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Case::ALL
+            .into_iter()
+            .find(|case| case.name() == s)
+            .ok_or_else(|| format!("Unknown case: {} (expected kebab, snake or camel)", s))
+    }
+}
+
+/// The words of `name`: runs of letters and digits, also split where a
+/// lowercase letter meets an uppercase one (`myName`) and before the last
+/// capital of an acronym (`HTTPServer`).
+pub fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = nfc(name).chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if let Some(&prev) = i.checked_sub(1).map(|p| &chars[p]) {
+            let next = chars.get(i + 1).copied();
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `s` in Unicode normalization form C, so a name typed or pasted with
+/// combining accents (`e` + U+0301) is stored with precomposed characters
+/// (`é`) and matches the same name typed the other way.
+pub fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
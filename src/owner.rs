@@ -0,0 +1,146 @@
+//! Owners and groups for `--owner` and `--group`, looked up in the system's
+//! user and group databases: `/etc/passwd` and `/etc/group`, or LDAP, SSSD
+//! and whatever else NSS is configured to ask.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fmt;
+use std::ptr;
+use std::str::FromStr;
+
+/// A user and/or group to give created directories and files to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Owner {
+    /// Name as given and resolved user id.
+    pub user: Option<(String, u32)>,
+    pub group: Option<(String, u32)>,
+}
+
+impl Owner {
+    pub fn uid(&self) -> Option<u32> {
+        self.user.as_ref().map(|(_, id)| *id)
+    }
+
+    pub fn gid(&self) -> Option<u32> {
+        self.group.as_ref().map(|(_, id)| *id)
+    }
+
+    /// Set the group from a name or numeric id.
+    pub fn set_group(&mut self, name: &str) -> Result<(), String> {
+        let id = resolve(name, "group", group_id)?;
+        self.group = Some((name.to_string(), id));
+        Ok(())
+    }
+}
+
+/// Parse `user`, `user:group`, `user:` (the user's login group) or `:group`.
+impl FromStr for Owner {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Owner, String> {
+        let (user, group) = match spec.split_once(':') {
+            Some((user, group)) => (user, Some(group)),
+            None => (spec, None),
+        };
+        let mut owner = Owner::default();
+        if !user.is_empty() {
+            owner.user = Some((user.to_string(), resolve(user, "user", user_id)?));
+        }
+        match group {
+            Some("") if !user.is_empty() => {
+                let gid =
+                    login_group(user).ok_or_else(|| format!("User {} has no login group", user))?;
+                owner.group = Some((user.to_string(), gid));
+            }
+            Some(group) => owner.set_group(group)?,
+            None => {}
+        }
+        if owner == Owner::default() {
+            return Err("Owner must name a user, a group or both".to_string());
+        }
+        Ok(owner)
+    }
+}
+
+impl fmt::Display for Owner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((user, _)) = &self.user {
+            write!(f, "{}", user)?;
+        }
+        if let Some((group, _)) = &self.group {
+            write!(f, ":{}", group)?;
+        }
+        Ok(())
+    }
+}
+
+/// Look a name up, falling back to reading it as a numeric id.
+fn resolve(name: &str, kind: &str, lookup: fn(&str) -> Option<u32>) -> Result<u32, String> {
+    lookup(name)
+        .or_else(|| name.parse().ok())
+        .ok_or_else(|| format!("No such {}: {}", kind, name))
+}
+
+/// Look an entry up with one of the reentrant `getpw*_r` / `getgr*_r`
+/// functions, growing the buffer for its strings as needed, and `read`
+/// what is wanted from it.
+fn get_entry<T, R>(
+    find: impl Fn(*mut T, *mut c_char, usize, *mut *mut T) -> c_int,
+    read: impl FnOnce(&T) -> R,
+) -> Option<R> {
+    let mut size = 1024;
+    loop {
+        let mut buffer = vec![0 as c_char; size];
+        // SAFETY: `passwd` and `group` are plain C structs; all zeroes is valid.
+        let mut entry: T = unsafe { std::mem::zeroed() };
+        let mut result = ptr::null_mut();
+        match find(&mut entry, buffer.as_mut_ptr(), buffer.len(), &mut result) {
+            libc::ERANGE if size < 1 << 20 => size *= 4,
+            0 if !result.is_null() => return Some(read(&entry)),
+            _ => return None,
+        }
+    }
+}
+
+fn passwd_by_name<R>(name: &str, read: impl FnOnce(&libc::passwd) -> R) -> Option<R> {
+    let name = CString::new(name).ok()?;
+    // SAFETY: `get_entry` passes pointers valid for the call and the buffer's length.
+    get_entry(
+        |entry, buffer, len, result| unsafe {
+            libc::getpwnam_r(name.as_ptr(), entry, buffer, len, result)
+        },
+        read,
+    )
+}
+
+pub fn user_id(name: &str) -> Option<u32> {
+    passwd_by_name(name, |entry| entry.pw_uid)
+}
+
+/// The login group of the user `name`.
+fn login_group(name: &str) -> Option<u32> {
+    passwd_by_name(name, |entry| entry.pw_gid)
+}
+
+pub fn user_name(uid: u32) -> Option<String> {
+    // SAFETY: `get_entry` passes pointers valid for the call and the buffer's
+    // length, and `pw_name` points into the buffer while `read` runs.
+    get_entry(
+        |entry, buffer, len, result| unsafe { libc::getpwuid_r(uid, entry, buffer, len, result) },
+        |entry: &libc::passwd| {
+            unsafe { CStr::from_ptr(entry.pw_name) }
+                .to_string_lossy()
+                .into_owned()
+        },
+    )
+}
+
+pub fn group_id(name: &str) -> Option<u32> {
+    let name = CString::new(name).ok()?;
+    // SAFETY: `get_entry` passes pointers valid for the call and the buffer's length.
+    get_entry(
+        |entry, buffer, len, result| unsafe {
+            libc::getgrnam_r(name.as_ptr(), entry, buffer, len, result)
+        },
+        |entry: &libc::group| entry.gr_gid,
+    )
+}
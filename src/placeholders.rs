@@ -0,0 +1,158 @@
+//! Date and variable placeholders in directory arguments.
+//!
+//! - `%Y-%m-%d`, `%G-W%V` and the other date conversions in [`CONVERSIONS`],
+//!   as `strftime(3)` formats them in local time, and `%%` for a `%`. Any
+//!   other `%`, as in names copied from URLs (`x%20y`), is kept as it is.
+//!   `SOURCE_DATE_EPOCH` pins the date, for scripts and tests.
+//! - `{user}` and `{hostname}`.
+//! - `{seq}`, or `{seq:3}` zero-padded to three digits: the first number from 1
+//!   up for which the directory doesn't exist yet.
+
+use crate::error::DrakoError;
+use crate::owner;
+use std::env;
+use std::ffi::{c_char, CStr, CString};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Fill in every placeholder in `arg`. `claimed` holds the names already
+/// taken in this run, so two `{seq}` arguments don't get the same number.
+pub fn substitute(arg: &str, claimed: &[String]) -> Result<String, DrakoError> {
+    let mut name = arg.to_string();
+    if name.contains('%') {
+        name = format_time(&name, now()?)?;
+    }
+    if name.contains("{user}") {
+        name = name.replace("{user}", &user()?);
+    }
+    if name.contains("{hostname}") {
+        name = name.replace("{hostname}", &hostname()?);
+    }
+    if let Some(widths) = sequence_widths(&name) {
+        name = next_free(&name, &widths, claimed);
+    }
+    Ok(name)
+}
+
+/// Seconds since the epoch, or `SOURCE_DATE_EPOCH` if set.
+fn now() -> Result<i64, DrakoError> {
+    match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse().map_err(|_| {
+            DrakoError::Usage(format!("SOURCE_DATE_EPOCH is not a number: {}", epoch))
+        }),
+        Err(_) => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64)),
+    }
+}
+
+/// `$USER`, else the name the user database gives the current user id.
+fn user() -> Result<String, DrakoError> {
+    if let Some(user) = env::var("USER")
+        .or_else(|_| env::var("LOGNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+    {
+        return Ok(user);
+    }
+    // SAFETY: getuid has no preconditions and cannot fail.
+    owner::user_name(unsafe { libc::getuid() })
+        .ok_or_else(|| DrakoError::Usage("Can't fill in {user}: USER is not set".to_string()))
+}
+
+fn hostname() -> Result<String, DrakoError> {
+    let mut buffer = [0 as c_char; 256];
+    // SAFETY: the buffer is valid for its length and the last byte stays NUL.
+    let status = unsafe { libc::gethostname(buffer.as_mut_ptr(), buffer.len() - 1) };
+    // SAFETY: the buffer is NUL-terminated.
+    let name = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    match status {
+        0 if !name.to_bytes().is_empty() => Ok(name.to_string_lossy().into_owned()),
+        _ => Err(DrakoError::Usage(
+            "Can't fill in {hostname}: the host name is unknown".to_string(),
+        )),
+    }
+}
+
+/// Widths of the `{seq}` / `{seq:N}` placeholders in `name`, if it has any.
+fn sequence_widths(name: &str) -> Option<Vec<(String, usize)>> {
+    let mut found = Vec::new();
+    let mut rest = name;
+    while let Some(start) = rest.find("{seq") {
+        let tail = &rest[start..];
+        let Some(end) = tail.find('}') else { break };
+        let token = &tail[..=end];
+        let width = match &token[4..token.len() - 1] {
+            "" => Some(0),
+            spec => spec.strip_prefix(':').and_then(|w| w.parse().ok()),
+        };
+        if let Some(width) = width {
+            found.push((token.to_string(), width));
+        }
+        rest = &tail[end + 1..];
+    }
+    (!found.is_empty()).then_some(found)
+}
+
+fn next_free(name: &str, widths: &[(String, usize)], claimed: &[String]) -> String {
+    (1u64..)
+        .map(|n| {
+            widths
+                .iter()
+                .fold(name.to_string(), |name, (token, width)| {
+                    name.replace(token, &format!("{:0width$}", n, width = width))
+                })
+        })
+        .find(|candidate| !Path::new(candidate).exists() && !claimed.contains(candidate))
+        .expect("some sequence number is free")
+}
+
+/// The `strftime(3)` conversions filled in, by their letter after `%`.
+pub const CONVERSIONS: &str = "YyCmdejHIMSpaAbBuwGgVUWFTsz";
+
+/// Format `time` as local time with the `%` conversions in `format`. Only
+/// [`CONVERSIONS`] and `%%` are replaced; everything else is kept.
+pub fn format_time(format: &str, time: i64) -> Result<String, DrakoError> {
+    let invalid = || DrakoError::Usage(format!("Can't fill in the date in {}", format));
+    let time = libc::time_t::try_from(time).map_err(|_| invalid())?;
+    // SAFETY: `tm` is plain data; all zeroes is a valid value.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: both pointers are valid for the duration of the call.
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return Err(invalid());
+    }
+
+    let mut formatted = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('%', Some('%')) => {
+                chars.next();
+                formatted.push('%');
+            }
+            ('%', Some(conversion)) if CONVERSIONS.contains(conversion) => {
+                chars.next();
+                formatted.push_str(&strftime(conversion, &tm).ok_or_else(invalid)?);
+            }
+            (c, _) => formatted.push(c),
+        }
+    }
+    Ok(formatted)
+}
+
+/// The single conversion `%<conversion>` of `tm`.
+fn strftime(conversion: char, tm: &libc::tm) -> Option<String> {
+    let format = CString::new(format!("%{}", conversion)).ok()?;
+    let mut buffer = [0u8; 64];
+    // SAFETY: the buffer is valid for `buffer.len()` bytes and `tm` is filled in.
+    let len = unsafe {
+        libc::strftime(
+            buffer.as_mut_ptr() as *mut c_char,
+            buffer.len(),
+            format.as_ptr(),
+            tm,
+        )
+    };
+    // `%p` may legitimately be empty in some locales.
+    String::from_utf8(buffer[..len].to_vec()).ok()
+}
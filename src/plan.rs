@@ -0,0 +1,747 @@
+use crate::error::DrakoError;
+use crate::files::{
+    backup_path, write_file, write_template, ConflictPolicy, ConflictRules, Template, Written,
+};
+use crate::flags::{Action, FlagSpec, NAME_OPTIONS};
+use crate::mode::Mode;
+use crate::naming::Case;
+use crate::owner::Owner;
+use crate::utils::*;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A directory drako should create, and what to set up inside it.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectSpec {
+    pub dir: String,
+    pub mode: Option<Mode>,
+    /// Mode for the parents of `dir` that don't exist yet and get created
+    /// along with it. Parents that already exist are left alone.
+    pub parents_mode: Option<Mode>,
+    /// Owner for every directory and file drako creates.
+    pub owner: Option<Owner>,
+    /// Extended attributes for every directory drako creates.
+    pub xattrs: Vec<(String, String)>,
+    /// Give generated files the extended attributes too.
+    pub xattr_files: bool,
+    /// Make every directory group-writable and setgid, with a default ACL
+    /// that keeps what is created in it later group-writable too.
+    pub shared: bool,
+    pub flags: Vec<&'static FlagSpec>,
+    /// Set up a directory that already exists instead of creating it.
+    pub existing: bool,
+    /// Carry on if the directory already exists.
+    pub exist_ok: bool,
+    /// What to do with files that are already there.
+    pub conflicts: ConflictRules,
+    /// Directories and files to create inside `dir`, parents first.
+    pub entries: Vec<Entry>,
+    /// Placeholder file to put in leaf directories that are empty once
+    /// everything else is done, so git keeps them.
+    pub keep: Option<String>,
+    /// The project's name as typed, for generated files, when `dir` was
+    /// made from it with `--slug` or `--case`.
+    pub title: Option<String>,
+}
+
+impl ProjectSpec {
+    pub fn new(dir: &str) -> Self {
+        ProjectSpec {
+            dir: dir.to_string(),
+            ..ProjectSpec::default()
+        }
+    }
+}
+
+/// Whether an [`Entry`] is a directory or an empty file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Dir,
+    File,
+}
+
+/// A directory or empty file inside a project, from a tree expression or file.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Relative to the project directory.
+    pub path: PathBuf,
+    pub kind: EntryKind,
+    /// Overrides the project's mode for this entry.
+    pub mode: Option<Mode>,
+    /// Project flags to apply inside this directory.
+    pub flags: Vec<&'static FlagSpec>,
+}
+
+impl Entry {
+    pub fn dir(path: impl Into<PathBuf>) -> Entry {
+        Entry {
+            path: path.into(),
+            kind: EntryKind::Dir,
+            mode: None,
+            flags: Vec::new(),
+        }
+    }
+}
+
+/// A single side effect drako performs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    CreateDir {
+        path: PathBuf,
+    },
+    /// Check that a directory exists, for `drako add`.
+    UseDir {
+        path: PathBuf,
+    },
+    SetPermissions {
+        path: PathBuf,
+        mode: Mode,
+    },
+    /// Give a directory a default ACL matching its current mode.
+    SetDefaultAcl {
+        path: PathBuf,
+    },
+    /// Change the owner of something the step before just created.
+    SetOwner {
+        path: PathBuf,
+        owner: Owner,
+    },
+    /// Move a project built by [`Plan::execute_atomically`] into place.
+    Commit {
+        staging: PathBuf,
+        path: PathBuf,
+    },
+    /// Remove an unfinished staged project and the parents created for it.
+    RollBack {
+        staging: PathBuf,
+        parents: Vec<PathBuf>,
+    },
+    /// Set an extended attribute on something the step before just created.
+    SetXattr {
+        path: PathBuf,
+        name: String,
+        value: String,
+    },
+    WriteFile {
+        path: PathBuf,
+        template: Template,
+        /// Project name for the template, instead of a placeholder.
+        title: Option<String>,
+        conflict: ConflictPolicy,
+    },
+    /// Create an empty file.
+    CreateFile {
+        path: PathBuf,
+        conflict: ConflictPolicy,
+    },
+    /// Create an empty placeholder file, if its directory is still empty.
+    KeepFile {
+        path: PathBuf,
+    },
+    /// Run a shell command in `dir`. With an owner, what the command creates
+    /// is given to them.
+    RunCommand {
+        dir: PathBuf,
+        command: String,
+        owner: Option<Owner>,
+    },
+}
+
+impl Step {
+    /// The path the step works on: the directory, for commands.
+    pub fn path(&self) -> &Path {
+        match self {
+            Step::CreateDir { path }
+            | Step::UseDir { path }
+            | Step::SetPermissions { path, .. }
+            | Step::SetDefaultAcl { path }
+            | Step::SetOwner { path, .. }
+            | Step::SetXattr { path, .. }
+            | Step::WriteFile { path, .. }
+            | Step::CreateFile { path, .. }
+            | Step::KeepFile { path }
+            | Step::Commit { path, .. } => path,
+            Step::RollBack { staging, .. } => staging,
+            Step::RunCommand { dir, .. } => dir,
+        }
+    }
+
+    /// The same step with paths under `from` moved under `to`. Commands
+    /// keep their text, so they still name the final directory.
+    fn relocated(&self, from: &Path, to: &Path) -> Step {
+        let mut step = self.clone();
+        let path = match &mut step {
+            Step::CreateDir { path }
+            | Step::UseDir { path }
+            | Step::SetPermissions { path, .. }
+            | Step::SetDefaultAcl { path }
+            | Step::SetOwner { path, .. }
+            | Step::SetXattr { path, .. }
+            | Step::WriteFile { path, .. }
+            | Step::CreateFile { path, .. }
+            | Step::KeepFile { path }
+            | Step::Commit { path, .. } => path,
+            Step::RollBack { staging, .. } => staging,
+            Step::RunCommand { dir, .. } => dir,
+        };
+        if let Ok(rel) = path.strip_prefix(from) {
+            *path = to.join(rel);
+        }
+        step
+    }
+
+    /// Steps that only make sense if the step before them created something.
+    fn is_follow_up(&self) -> bool {
+        matches!(self, Step::SetOwner { .. } | Step::SetXattr { .. })
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::CreateDir { path } => write!(f, "create directory {}", path.display()),
+            Step::UseDir { path } => write!(f, "use existing directory {}", path.display()),
+            Step::SetPermissions { path, mode } => {
+                write!(f, "set permissions {} on {}", mode, path.display())
+            }
+            Step::SetDefaultAcl { path } => write!(f, "set default ACL on {}", path.display()),
+            Step::SetOwner { path, owner } => {
+                write!(f, "give {} to {}", path.display(), owner)
+            }
+            Step::SetXattr { path, name, value } => {
+                write!(f, "set {}={} on {}", name, value, path.display())
+            }
+            Step::Commit { staging, path } => {
+                write!(f, "move {} to {}", staging.display(), path.display())
+            }
+            Step::RollBack { staging, .. } => write!(f, "remove {}", staging.display()),
+            Step::WriteFile { path, .. } => write!(f, "write {}", path.display()),
+            Step::CreateFile { path, .. } => write!(f, "create file {}", path.display()),
+            Step::KeepFile { path } => {
+                write!(f, "create {} if its directory is empty", path.display())
+            }
+            Step::RunCommand {
+                dir,
+                command,
+                owner,
+            } => {
+                write!(f, "run `{}` in {}", command, dir.display())?;
+                match owner {
+                    Some(owner) => write!(f, " and give what it creates to {}", owner),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// What happened when a step ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    /// Would run; reported by [`Plan::dry_run`] instead of `Done`.
+    Planned,
+    /// Done, after moving the file that was in the way to this path.
+    BackedUp(PathBuf),
+    /// A command succeeded; what it wrote to stderr.
+    Ran(String),
+    /// Nothing needed doing, or an earlier step failed.
+    Skipped(String),
+    Failed(DrakoError),
+}
+
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub step: Step,
+    pub outcome: Outcome,
+}
+
+/// Receives progress while a plan executes.
+pub trait Reporter {
+    fn step_started(&mut self, _step: &Step) {}
+    fn step_finished(&mut self, _result: &StepResult) {}
+}
+
+/// A reporter that ignores everything.
+impl Reporter for () {}
+
+/// Ancestors of `path` that don't exist yet, innermost first.
+pub fn missing_parents(path: &Path) -> impl Iterator<Item = &Path> {
+    path.ancestors()
+        .skip(1)
+        .take_while(|p| !p.as_os_str().is_empty() && !p.exists())
+}
+
+/// Group-writable and setgid, so files made later get the directory's group.
+fn share_steps(path: &Path) -> [Step; 2] {
+    [
+        Step::SetPermissions {
+            path: path.to_path_buf(),
+            mode: "g+rwxs".parse().expect("valid mode"),
+        },
+        Step::SetDefaultAcl {
+            path: path.to_path_buf(),
+        },
+    ]
+}
+
+/// Placeholder files for the root and entry directories that have no
+/// entries inside them. Whether they are still empty is checked when the
+/// steps run, after the project flags.
+fn keep_steps(root: &Path, entries: &[Entry], name: &str) -> Vec<Step> {
+    let dirs = entries
+        .iter()
+        .filter(|entry| entry.kind == EntryKind::Dir)
+        .map(|entry| root.join(&entry.path));
+    std::iter::once(root.to_path_buf())
+        .chain(dirs)
+        .filter(|dir| {
+            !entries
+                .iter()
+                .any(|entry| root.join(&entry.path).parent() == Some(dir.as_path()))
+        })
+        .map(|dir| Step::KeepFile {
+            path: dir.join(name),
+        })
+        .collect()
+}
+
+/// Follow every step that creates something with the steps giving it the
+/// project's owner and extended attributes. Commands give what they create
+/// to the owner themselves.
+fn with_follow_ups(steps: Vec<Step>, spec: &ProjectSpec) -> Vec<Step> {
+    let mut followed = Vec::new();
+    for mut step in steps {
+        if let Step::RunCommand { owner, .. } = &mut step {
+            owner.clone_from(&spec.owner);
+        }
+        let created = match &step {
+            Step::CreateDir { path } => Some((path.clone(), true)),
+            Step::WriteFile { path, .. }
+            | Step::CreateFile { path, .. }
+            | Step::KeepFile { path } => Some((path.clone(), spec.xattr_files)),
+            _ => None,
+        };
+        followed.push(step);
+        let Some((path, xattrs)) = created else {
+            continue;
+        };
+        if let Some(owner) = &spec.owner {
+            followed.push(Step::SetOwner {
+                path: path.clone(),
+                owner: owner.clone(),
+            });
+        }
+        if xattrs {
+            for (name, value) in &spec.xattrs {
+                followed.push(Step::SetXattr {
+                    path: path.clone(),
+                    name: name.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+    followed
+}
+
+/// The ordered steps needed to set up one project.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub root: PathBuf,
+    pub steps: Vec<Step>,
+    /// An existing root directory is used rather than reported as an error.
+    pub exist_ok: bool,
+}
+
+impl Plan {
+    pub fn for_project(spec: &ProjectSpec) -> Plan {
+        let root = PathBuf::from(&spec.dir);
+        let mut steps = vec![if spec.existing {
+            Step::UseDir { path: root.clone() }
+        } else {
+            Step::CreateDir { path: root.clone() }
+        }];
+        // The parents `create_dir_all` makes along the way are this run's too.
+        if let Some(owner) = spec.owner.as_ref().filter(|_| !spec.existing) {
+            for parent in missing_parents(&root) {
+                steps.push(Step::SetOwner {
+                    path: parent.to_path_buf(),
+                    owner: owner.clone(),
+                });
+            }
+        }
+
+        if let Some(mode) = spec.mode.clone() {
+            steps.push(Step::SetPermissions {
+                path: root.clone(),
+                mode,
+            });
+        }
+        if spec.shared {
+            steps.extend(share_steps(&root));
+        }
+
+        // Entry permissions come last, so a read-only directory can still be filled.
+        let mut permissions = Vec::new();
+        for entry in &spec.entries {
+            let path = root.join(&entry.path);
+            let mode = match entry.kind {
+                // `drako add` fills in what is missing from an existing tree.
+                EntryKind::Dir if spec.existing && path.is_dir() => {
+                    steps.push(Step::UseDir { path: path.clone() });
+                    entry.mode.clone().or(spec.mode.clone())
+                }
+                EntryKind::Dir => {
+                    steps.push(Step::CreateDir { path: path.clone() });
+                    entry.mode.clone().or(spec.mode.clone())
+                }
+                EntryKind::File => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    steps.push(Step::CreateFile {
+                        path: path.clone(),
+                        conflict: spec.conflicts.policy_for(&name),
+                    });
+                    entry.mode.clone()
+                }
+            };
+            if let Some(mode) = mode {
+                permissions.push(Step::SetPermissions {
+                    path: path.clone(),
+                    mode,
+                });
+            }
+            if spec.shared && entry.kind == EntryKind::Dir {
+                permissions.extend(share_steps(&path));
+            }
+        }
+
+        for mut step in flag_steps(&root, &spec.flags, &spec.conflicts) {
+            match &mut step {
+                Step::WriteFile { title, .. } => title.clone_from(&spec.title),
+                Step::RunCommand { command, .. } => {
+                    let option = NAME_OPTIONS.iter().find(|(cmd, _)| cmd == command);
+                    if let (Some((_, option)), Some(title)) = (option, &spec.title) {
+                        // Snake case only leaves letters, digits and `_` for the shell.
+                        command.push_str(&format!(" {} {}", option, Case::Snake.apply(title)));
+                    }
+                }
+                _ => {}
+            }
+            steps.push(step);
+        }
+        for entry in &spec.entries {
+            steps.extend(flag_steps(
+                &root.join(&entry.path),
+                &entry.flags,
+                &spec.conflicts,
+            ));
+        }
+        if let Some(name) = &spec.keep {
+            steps.extend(keep_steps(&root, &spec.entries, name));
+        }
+        steps.extend(permissions);
+        steps = with_follow_ups(steps, spec);
+        if let Some(mode) = spec.parents_mode.as_ref().filter(|_| !spec.existing) {
+            // Innermost first, in case the mode takes away search permission.
+            for parent in missing_parents(&root) {
+                steps.push(Step::SetPermissions {
+                    path: parent.to_path_buf(),
+                    mode: mode.clone(),
+                });
+            }
+        }
+        Plan {
+            root,
+            steps,
+            exist_ok: spec.exist_ok,
+        }
+    }
+
+    /// Run every step in order. If the project directory can't be created
+    /// (or, for `drako add`, doesn't exist), the remaining steps are skipped.
+    pub fn execute(&self, reporter: &mut dyn Reporter) -> Report {
+        self.run(&self.steps, reporter, RunMode::Execute)
+    }
+
+    /// Like [`execute`](Plan::execute), but all or nothing: the project is
+    /// built in a hidden staging directory next to the root and only renamed
+    /// into place once every step has succeeded. After the first failure the
+    /// remaining steps are skipped and everything created so far is removed,
+    /// parents included.
+    pub fn execute_atomically(&self, reporter: &mut dyn Reporter) -> Report {
+        let name = self
+            .root
+            .file_name()
+            .unwrap_or(self.root.as_os_str())
+            .to_string_lossy();
+        let staging = self
+            .root
+            .with_file_name(format!(".{}.drako-{}", name, std::process::id()));
+        let staged_root = staging.join(&*name);
+        let parents = missing_parents(&staging).map(Path::to_path_buf).collect();
+        // Parent permissions wait until the project is in place.
+        let (inside, outside): (Vec<Step>, Vec<Step>) = self
+            .steps
+            .iter()
+            .cloned()
+            .partition(|step| step.path().starts_with(&self.root));
+
+        let mut report = self.run(&inside, reporter, RunMode::Staged(&staged_root));
+        if report.is_success() {
+            let commit = Step::Commit {
+                staging: staged_root,
+                path: self.root.clone(),
+            };
+            let commit = self.run(&[commit], reporter, RunMode::Execute);
+            let committed = commit.is_success();
+            report.results.extend(commit.results);
+            if committed {
+                report
+                    .results
+                    .extend(self.run(&outside, reporter, RunMode::Execute).results);
+                return report;
+            }
+        }
+        if staging.exists() {
+            let roll_back = Step::RollBack { staging, parents };
+            report
+                .results
+                .extend(self.run(&[roll_back], reporter, RunMode::Execute).results);
+        }
+        report
+    }
+
+    /// Report what [`execute`](Plan::execute) would do without touching the disk.
+    ///
+    /// Every directory `create_dir_all` would make is listed, outermost first,
+    /// and outcomes that can be foreseen (an existing directory, a file
+    /// conflict) are reported as they would happen. `planned` holds the
+    /// directories earlier dry runs would create, so parents shared between
+    /// projects are listed once; this run's directories are added to it.
+    pub fn dry_run(&self, reporter: &mut dyn Reporter, planned: &mut HashSet<PathBuf>) -> Report {
+        let mut steps = Vec::new();
+        for step in &self.steps {
+            if let Step::CreateDir { path } = step {
+                let mut missing: Vec<&Path> = path
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|p| {
+                        !p.as_os_str().is_empty() && !p.exists() && !planned.contains(*p)
+                    })
+                    .collect();
+                missing.reverse();
+                planned.extend(missing.iter().map(|p| p.to_path_buf()));
+                planned.insert(path.clone());
+                steps.extend(missing.into_iter().map(|p| Step::CreateDir {
+                    path: p.to_path_buf(),
+                }));
+            }
+            steps.push(step.clone());
+        }
+        self.run(&steps, reporter, RunMode::DryRun)
+    }
+
+    fn run(&self, steps: &[Step], reporter: &mut dyn Reporter, mode: RunMode) -> Report {
+        let mut report = Report::default();
+        let mut blocked: Option<String> = None;
+
+        for step in steps {
+            let created = report
+                .results
+                .iter()
+                .rev()
+                .find(|r| !r.step.is_follow_up())
+                .map(|r| &r.outcome)
+                .filter(|_| step.is_follow_up());
+            let outcome = match (&blocked, created) {
+                (Some(reason), _) => Outcome::Skipped(reason.clone()),
+                // Only what drako created changes hands or gets attributes.
+                (None, Some(Outcome::Skipped(_) | Outcome::Failed(_))) => {
+                    Outcome::Skipped("Nothing was created".to_string())
+                }
+                _ => match mode {
+                    RunMode::DryRun => predict_step(step),
+                    RunMode::Execute => {
+                        reporter.step_started(step);
+                        run_step(step)
+                    }
+                    RunMode::Staged(_) if step.path() == self.root && self.root.exists() => {
+                        Outcome::Failed(DrakoError::AlreadyExists(self.root.clone()))
+                    }
+                    RunMode::Staged(staged_root) => {
+                        reporter.step_started(step);
+                        run_step(&step.relocated(&self.root, staged_root))
+                    }
+                },
+            };
+            let outcome = match outcome {
+                Outcome::Failed(DrakoError::AlreadyExists(path))
+                    if self.exist_ok && path.is_dir() && !matches!(mode, RunMode::Staged(_)) =>
+                {
+                    Outcome::Skipped("Directory already exists".to_string())
+                }
+                outcome => outcome,
+            };
+
+            if blocked.is_none() && matches!(outcome, Outcome::Failed(_)) {
+                match step {
+                    Step::CreateDir { path } | Step::UseDir { path } if path == &self.root => {
+                        blocked = Some(format!("{} is not available", path.display()));
+                    }
+                    _ if matches!(mode, RunMode::Staged(_)) => {
+                        blocked = Some("An earlier step failed".to_string());
+                    }
+                    _ => {}
+                }
+            }
+
+            let result = StepResult {
+                step: step.clone(),
+                outcome,
+            };
+            reporter.step_finished(&result);
+            report.results.push(result);
+        }
+
+        report
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RunMode<'a> {
+    Execute,
+    DryRun,
+    /// Execute with the root moved to this staging path.
+    Staged(&'a Path),
+}
+
+/// The result of executing a plan.
+#[derive(Debug, Default, Clone)]
+pub struct Report {
+    pub results: Vec<StepResult>,
+}
+
+impl Report {
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &DrakoError> {
+        self.results.iter().filter_map(|r| match &r.outcome {
+            Outcome::Failed(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Exit code of the first failure, or 0 if nothing failed.
+    pub fn exit_code(&self) -> i32 {
+        self.failures().next().map_or(0, DrakoError::exit_code)
+    }
+}
+
+/// Steps for the project flags given for a directory.
+pub fn flag_steps(dir: &Path, flags: &[&FlagSpec], conflicts: &ConflictRules) -> Vec<Step> {
+    flags
+        .iter()
+        .filter_map(|flag| match flag.action {
+            Action::Run(cmd) => Some(Step::RunCommand {
+                dir: dir.to_path_buf(),
+                command: cmd.replace("{dir}", &dir.to_string_lossy()),
+                owner: None,
+            }),
+            Action::Write(template) => Some(Step::WriteFile {
+                path: dir.join(template.file_name()),
+                template,
+                title: None,
+                conflict: conflicts.policy_for(template.file_name()),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The outcome `run_step` would have, judged from what is on disk now.
+pub fn predict_step(step: &Step) -> Outcome {
+    match step {
+        Step::CreateDir { path } if path.exists() => {
+            Outcome::Failed(DrakoError::AlreadyExists(path.clone()))
+        }
+        Step::UseDir { path } => match check_directory(path) {
+            Ok(()) => Outcome::Planned,
+            Err(e) => Outcome::Failed(e),
+        },
+        Step::WriteFile { path, conflict, .. } | Step::CreateFile { path, conflict }
+            if path.exists() =>
+        {
+            match conflict {
+                ConflictPolicy::Skip => Outcome::Skipped("File already exists".to_string()),
+                ConflictPolicy::Backup => Outcome::BackedUp(backup_path(path)),
+                ConflictPolicy::Fail => Outcome::Failed(DrakoError::Conflict(path.clone())),
+                ConflictPolicy::Overwrite | ConflictPolicy::Prompt => Outcome::Planned,
+            }
+        }
+        _ => Outcome::Planned,
+    }
+}
+
+fn written(result: Result<Written, DrakoError>) -> Outcome {
+    match result {
+        Ok(Written::Created | Written::Overwritten) => Outcome::Done,
+        Ok(Written::BackedUp(backup)) => Outcome::BackedUp(backup),
+        Ok(Written::Kept) => Outcome::Skipped("File already exists".to_string()),
+        Err(e) => Outcome::Failed(e),
+    }
+}
+
+pub fn run_step(step: &Step) -> Outcome {
+    let result = match step {
+        Step::CreateDir { path } => create_directory(path),
+        Step::UseDir { path } => check_directory(path),
+        Step::SetPermissions { path, mode } => set_permissions(path, mode),
+        Step::SetOwner { path, owner } => set_owner(path, owner),
+        Step::SetXattr { path, name, value } => set_xattr(path, name, value),
+        Step::Commit { staging, path } => commit_staged(staging, path),
+        Step::RollBack { staging, parents } => roll_back(staging, parents),
+        Step::SetDefaultAcl { path } => match set_default_acl(path) {
+            Ok(false) => {
+                return Outcome::Skipped("Default ACLs are not supported here".to_string())
+            }
+            result => result.map(|_| ()),
+        },
+        Step::WriteFile {
+            path,
+            template,
+            title,
+            conflict,
+        } => return written(write_template(path, *template, title.as_deref(), *conflict)),
+        Step::CreateFile { path, conflict } => return written(write_file(path, "", *conflict)),
+        Step::KeepFile { path } => {
+            let dir = path.parent().unwrap_or(path);
+            match dir.read_dir().map(|mut entries| entries.next().is_none()) {
+                Ok(true) => write_file(path, "", ConflictPolicy::Skip).map(|_| ()),
+                Ok(false) => return Outcome::Skipped("The directory is not empty".to_string()),
+                Err(e) => Err(DrakoError::io(dir, e)),
+            }
+        }
+        Step::RunCommand {
+            dir,
+            command,
+            owner,
+        } => {
+            let result = match owner {
+                Some(owner) => run_command_as(dir, command, owner),
+                None => run_command(dir, command),
+            };
+            return match result {
+                Ok(stderr) => Outcome::Ran(stderr),
+                Err(e) => Outcome::Failed(e),
+            };
+        }
+    };
+    match result {
+        Ok(()) => Outcome::Done,
+        Err(e) => Outcome::Failed(e),
+    }
+}
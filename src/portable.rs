@@ -0,0 +1,152 @@
+//! Checks that the names drako creates work on other systems too.
+//!
+//! A name that is fine on Linux can still break on a colleague's machine or
+//! in an archive: Windows rejects `:` and names like `CON` and drops trailing
+//! dots and spaces, macOS and Windows don't tell `Docs` from `docs`, and
+//! file systems cap the length of names and paths. These problems are always
+//! reported; `--portable` makes them errors and also holds names to the POSIX
+//! portable character set, `A-Z a-z 0-9 . _ -`.
+
+use crate::plan::{missing_parents, ProjectSpec};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The longest name most file systems accept, in bytes.
+pub const MAX_NAME_BYTES: usize = 255;
+/// The longest path Windows accepts without special prefixes.
+pub const MAX_PATH_CHARS: usize = 260;
+
+/// Characters Windows doesn't allow in names. macOS doesn't allow `:` either.
+const WINDOWS_FORBIDDEN: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Device names Windows reserves, with or without an extension.
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// A name or path that won't work everywhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Why `name` won't work everywhere. With `strict`, names outside the POSIX
+/// portable character set count too.
+pub fn name_problems(name: &str, strict: bool) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if name == "." || name == ".." {
+        return reasons;
+    }
+    if name.chars().any(char::is_control) {
+        reasons.push("it contains a control character".to_string());
+    }
+    let mut forbidden: Vec<char> = name
+        .chars()
+        .filter(|c| WINDOWS_FORBIDDEN.contains(c))
+        .collect();
+    forbidden.sort_unstable();
+    forbidden.dedup();
+    for c in forbidden {
+        let systems = if c == ':' {
+            "Windows and macOS don't"
+        } else {
+            "Windows doesn't"
+        };
+        reasons.push(format!("{} allow `{}` in names", systems, c));
+    }
+    if name.ends_with(' ') || name.ends_with('.') {
+        reasons.push("Windows drops trailing spaces and dots".to_string());
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if WINDOWS_RESERVED
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        reasons.push(format!("{} is a reserved name on Windows", stem));
+    }
+    if name.len() > MAX_NAME_BYTES {
+        reasons.push(format!("it is longer than {} bytes", MAX_NAME_BYTES));
+    }
+    if strict {
+        let mut others: Vec<char> = name
+            .chars()
+            .filter(|c| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '_' | '-'))
+            .filter(|c| !c.is_control() && !WINDOWS_FORBIDDEN.contains(c))
+            .collect();
+        others.sort_unstable();
+        others.dedup();
+        if !others.is_empty() {
+            let shown: String = others.iter().map(|c| format!("`{}`", c)).collect();
+            reasons.push(format!(
+                "{} is outside the POSIX portable character set",
+                shown
+            ));
+        }
+        if name.starts_with('-') {
+            reasons.push("it starts with `-`, which commands take for an option".to_string());
+        }
+    }
+    reasons
+}
+
+/// The paths `projects` would create, parents first, each listed once.
+pub fn new_paths(projects: &[ProjectSpec]) -> Vec<PathBuf> {
+    let mut new_paths: Vec<PathBuf> = Vec::new();
+    for spec in projects {
+        let root = Path::new(&spec.dir);
+        let mut paths: Vec<PathBuf> = Vec::new();
+        if !spec.existing && !root.exists() {
+            paths.extend(missing_parents(root).map(Path::to_path_buf));
+            paths.reverse();
+            paths.push(root.to_path_buf());
+        }
+        paths.extend(spec.entries.iter().map(|e| root.join(&e.path)));
+        for path in paths {
+            if !new_paths.contains(&path) {
+                new_paths.push(path);
+            }
+        }
+    }
+    new_paths
+}
+
+/// Problems with the paths about to be created: their own names, their
+/// length, and names that only differ in case from another one in `paths`.
+pub fn check(paths: &[PathBuf], strict: bool) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<String, &Path> = HashMap::new();
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        problems.extend(
+            name_problems(&name, strict)
+                .into_iter()
+                .map(|reason| Problem {
+                    path: path.clone(),
+                    reason,
+                }),
+        );
+        if path.to_string_lossy().chars().count() > MAX_PATH_CHARS {
+            problems.push(Problem {
+                path: path.clone(),
+                reason: format!("the path is longer than {} characters", MAX_PATH_CHARS),
+            });
+        }
+        let folded = path.to_string_lossy().to_lowercase();
+        match seen.get(&folded) {
+            Some(other) if *other != path.as_path() => problems.push(Problem {
+                path: path.clone(),
+                reason: format!(
+                    "it only differs in case from {}, the same name on macOS and Windows",
+                    other.display()
+                ),
+            }),
+            Some(_) => {}
+            None => {
+                seen.insert(folded, path);
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,242 @@
+//! Directory skeletons of existing projects, for `--like`.
+//!
+//! Only directories are copied, plus empty `.gitkeep` files where the source
+//! has them. `.git` is never copied, and anything matched by a `.gitignore`
+//! on the way down (`target/`, `node_modules/`, ...) is skipped.
+
+use crate::error::DrakoError;
+use crate::mode::Mode;
+use crate::plan::{Entry, EntryKind};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// File that keeps an otherwise empty directory in git.
+pub const GITKEEP: &str = ".gitkeep";
+
+/// The layout found under a source directory.
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    /// Mode of the source directory itself, if modes are copied.
+    pub mode: Option<Mode>,
+    /// Everything below it, parents first.
+    pub entries: Vec<Entry>,
+}
+
+/// Walk `source` and describe its directory hierarchy.
+pub fn scan(source: &Path, copy_modes: bool) -> Result<Skeleton, DrakoError> {
+    let metadata = fs::metadata(source).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => DrakoError::NotFound(source.to_path_buf()),
+        _ => DrakoError::io(source, e),
+    })?;
+    if !metadata.is_dir() {
+        return Err(DrakoError::Io {
+            path: source.to_path_buf(),
+            message: "not a directory".to_string(),
+        });
+    }
+
+    let mut skeleton = Skeleton {
+        mode: copy_modes.then(|| Mode::Octal(metadata.permissions().mode() & 0o7777)),
+        entries: Vec::new(),
+    };
+    let mut ignores = Vec::new();
+    walk(
+        source,
+        Path::new(""),
+        copy_modes,
+        &mut ignores,
+        &mut skeleton.entries,
+    )?;
+    Ok(skeleton)
+}
+
+fn walk(
+    dir: &Path,
+    rel: &Path,
+    copy_modes: bool,
+    ignores: &mut Vec<Ignore>,
+    entries: &mut Vec<Entry>,
+) -> Result<(), DrakoError> {
+    let pushed = match fs::read_to_string(dir.join(".gitignore")) {
+        Ok(text) => {
+            ignores.push(Ignore::parse(rel, &text));
+            true
+        }
+        Err(_) => false,
+    };
+
+    let mut children: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| DrakoError::io(dir, e))?
+        .filter_map(Result::ok)
+        .collect();
+    children.sort_by_key(|child| child.file_name());
+
+    for child in children {
+        let name = child.file_name();
+        let path = rel.join(&name);
+        // Symlinks are not followed.
+        let Ok(file_type) = child.file_type() else {
+            continue;
+        };
+        let is_dir = file_type.is_dir();
+        if name == ".git" || is_ignored(ignores, &path, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            let mode = if copy_modes {
+                child
+                    .metadata()
+                    .ok()
+                    .map(|m| Mode::Octal(m.permissions().mode() & 0o7777))
+            } else {
+                None
+            };
+            entries.push(Entry {
+                mode,
+                ..Entry::dir(path.clone())
+            });
+            walk(&child.path(), &path, copy_modes, ignores, entries)?;
+        } else if file_type.is_file() && name == GITKEEP {
+            entries.push(Entry {
+                kind: EntryKind::File,
+                ..Entry::dir(path)
+            });
+        }
+    }
+
+    if pushed {
+        ignores.pop();
+    }
+    Ok(())
+}
+
+/// Whether the last `.gitignore` rule matching `path` ignores it.
+fn is_ignored(ignores: &[Ignore], path: &Path, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for ignore in ignores {
+        let Ok(rel) = path.strip_prefix(&ignore.base) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy();
+        for rule in &ignore.rules {
+            if rule.matches(&rel, is_dir) {
+                ignored = !rule.negate;
+            }
+        }
+    }
+    ignored
+}
+
+/// The rules of one `.gitignore` file.
+struct Ignore {
+    /// Directory holding the file, relative to the source.
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    pattern: String,
+    negate: bool,
+    dir_only: bool,
+    /// Matched against the whole relative path rather than the last name.
+    anchored: bool,
+}
+
+impl Ignore {
+    fn parse(base: &Path, text: &str) -> Ignore {
+        let rules = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negate, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                Rule {
+                    pattern: line.trim_start_matches('/').to_string(),
+                    negate,
+                    dir_only,
+                    anchored,
+                }
+            })
+            .collect();
+        Ignore {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+}
+
+impl Rule {
+    fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let target = if self.anchored {
+            rel
+        } else {
+            rel.rsplit('/').next().unwrap_or(rel)
+        };
+        glob(self.pattern.as_bytes(), target.as_bytes())
+    }
+}
+
+/// Match gitignore-style globs: `*` and `?` within a name, `**` across
+/// directories, and `[...]` classes.
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            glob(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .any(|(i, c)| *c == b'/' && glob(rest, &text[i + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| glob(rest, &text[i..])),
+        [b'*', rest @ ..] => {
+            (0..=text.len()).any(|i| !text[..i].contains(&b'/') && glob(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => matches!(text, [c, tail @ ..] if *c != b'/' && glob(rest, tail)),
+        [b'[', rest @ ..] => {
+            let Some(end) = rest.iter().skip(1).position(|c| *c == b']').map(|i| i + 1) else {
+                return matches!(text, [b'[', tail @ ..] if glob(rest, tail));
+            };
+            let (class, rest) = (&rest[..end], &rest[end + 1..]);
+            match text {
+                [c, tail @ ..] => in_class(class, *c) && glob(rest, tail),
+                [] => false,
+            }
+        }
+        [b'\\', c, rest @ ..] | [c, rest @ ..] => {
+            matches!(text, [t, tail @ ..] if t == c && glob(rest, tail))
+        }
+    }
+}
+
+fn in_class(class: &[u8], c: u8) -> bool {
+    let (negate, class) = match class {
+        [b'!' | b'^', rest @ ..] => (true, rest),
+        _ => (false, class),
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == b'-' {
+            found |= (class[i]..=class[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= class[i] == c;
+            i += 1;
+        }
+    }
+    found != negate
+}
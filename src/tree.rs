@@ -0,0 +1,193 @@
+//! Directory tree expressions such as `app(src(bin),public,tests)`.
+//!
+//! ```text
+//! list := node ("," node)*
+//! node := name ["(" list ")"]
+//! name := bare | "..." | '...'
+//! ```
+//!
+//! Bare names run up to the next `(`, `)` or `,`, with surrounding spaces
+//! trimmed. Quoted names are taken literally.
+
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// One directory and the directories inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    pub name: String,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Every directory below this node, relative to it, parents first.
+    pub fn descendants(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for child in &self.children {
+            let path = PathBuf::from(&child.name);
+            paths.push(path.clone());
+            paths.extend(child.descendants().into_iter().map(|p| path.join(p)));
+        }
+        paths
+    }
+}
+
+/// A tree expression that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeError {
+    pub input: String,
+    /// 1-based column of the offending character.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid directory tree at column {}: {}\n  {}\n  {}^",
+            self.column,
+            self.message,
+            self.input,
+            " ".repeat(self.column - 1)
+        )
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+/// True if `arg` should be read as a tree expression rather than a plain
+/// path: a `(` follows a name directly, as in `app(src)`. With a space in
+/// between, as in `Photos (2024)`, the parentheses are part of the name.
+pub fn is_tree(arg: &str) -> bool {
+    let chars: Vec<char> = arg.chars().collect();
+    chars
+        .windows(2)
+        .any(|pair| pair[1] == '(' && !pair[0].is_whitespace())
+}
+
+/// Parse a tree expression into its top-level directories.
+pub fn parse(input: &str) -> Result<Vec<TreeNode>, TreeError> {
+    let mut parser = Parser {
+        input,
+        chars: input.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    let nodes = parser.list()?;
+    match parser.peek() {
+        None => Ok(nodes),
+        Some(')') => Err(parser.error("unmatched `)`")),
+        Some(c) => Err(parser.error(&format!("unexpected `{}`", c))),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+    /// How many `(` the parser is inside.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> TreeError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> TreeError {
+        TreeError {
+            input: self.input.to_string(),
+            column: pos + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn list(&mut self) -> Result<Vec<TreeNode>, TreeError> {
+        let mut nodes = vec![self.node()?];
+        while self.peek() == Some(',') {
+            self.pos += 1;
+            nodes.push(self.node()?);
+        }
+        Ok(nodes)
+    }
+
+    fn node(&mut self) -> Result<TreeNode, TreeError> {
+        self.skip_spaces();
+        let name = self.name()?;
+        self.skip_spaces();
+
+        let mut children = Vec::new();
+        if self.peek() == Some('(') {
+            let open = self.pos;
+            self.pos += 1;
+            self.depth += 1;
+            children = self.list()?;
+            self.depth -= 1;
+            if self.peek() != Some(')') {
+                return Err(match self.peek() {
+                    None => self.error_at(open, "`(` is never closed"),
+                    Some(c) => self.error(&format!("expected `,` or `)`, found `{}`", c)),
+                });
+            }
+            self.pos += 1;
+            self.skip_spaces();
+        }
+        Ok(TreeNode { name, children })
+    }
+
+    fn name(&mut self) -> Result<String, TreeError> {
+        let start = self.pos;
+        let name = match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                let from = self.pos;
+                while self.peek().is_some_and(|c| c != quote) {
+                    self.pos += 1;
+                }
+                if self.peek().is_none() {
+                    return Err(self.error_at(start, "quote is never closed"));
+                }
+                let name: String = self.chars[from..self.pos].iter().collect();
+                self.pos += 1;
+                name
+            }
+            _ => {
+                while self.peek().is_some_and(|c| !matches!(c, '(' | ')' | ',')) {
+                    if matches!(self.peek(), Some('"' | '\'')) {
+                        return Err(self.error("quotes must surround the whole name"));
+                    }
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                name.trim_end().to_string()
+            }
+        };
+
+        if name.is_empty() {
+            return Err(self.error_at(start, "expected a directory name"));
+        }
+        if name == ".." || name.starts_with("../") || name.contains("/../") || name.ends_with("/..")
+        {
+            return Err(self.error_at(start, "`..` is not allowed in a tree"));
+        }
+        // Joining an absolute path onto the parent would replace it.
+        let absolute = Path::new(&name)
+            .components()
+            .any(|c| matches!(c, Component::RootDir | Component::Prefix(_)));
+        if self.depth > 0 && absolute {
+            return Err(self.error_at(start, "directories inside a tree must be relative"));
+        }
+        Ok(name)
+    }
+}
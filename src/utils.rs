@@ -1,106 +1,168 @@
-use crate::files::*;
-use crate::messages::*;
+use crate::error::DrakoError;
+use crate::files::ConflictRules;
+use crate::flags::FlagSpec;
+use crate::mode::Mode;
+use crate::owner::Owner;
+use crate::plan::{flag_steps, run_step, StepResult};
+use crate::xattr;
+use std::collections::HashSet;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-pub fn create_directory(dir: &str, verbose: bool) -> bool {
-    let path = Path::new(dir);
+/// Exit status `sh` uses when the program can't be found.
+pub const COMMAND_NOT_FOUND: i32 = 127;
 
-    if path.exists() {
-        warning("Directory already exists", Some(dir));
-        return false;
+pub fn create_directory(dir: &Path) -> Result<(), DrakoError> {
+    if dir.exists() {
+        return Err(DrakoError::AlreadyExists(dir.to_path_buf()));
     }
 
-    match fs::create_dir_all(path) {
-        Ok(_) => {
-            if verbose {
-                match fs::canonicalize(path) {
-                    Ok(full_path) => {
-                        success("Created directory", Some(&full_path.display().to_string()))
-                    }
-                    Err(_) => success("Created directory", Some(dir)),
-                }
-            }
-            true
-        }
-        Err(_) => {
-            error("Failed to create directory", Some(dir));
-            false
+    fs::create_dir_all(dir).map_err(|e| DrakoError::io(dir, e))
+}
+
+/// Succeeds if `dir` is an existing directory.
+pub fn check_directory(dir: &Path) -> Result<(), DrakoError> {
+    match fs::metadata(dir) {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => Err(DrakoError::Io {
+            path: dir.to_path_buf(),
+            message: "not a directory".to_string(),
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(DrakoError::NotFound(dir.to_path_buf()))
         }
+        Err(e) => Err(DrakoError::io(dir, e)),
     }
 }
 
-pub fn set_permissions(dir: &str, mode: u32, verbose: bool) {
-    if let Ok(metadata) = fs::metadata(dir) {
-        let mut perms = metadata.permissions();
-        perms.set_mode(mode);
-        if fs::set_permissions(dir, perms).is_err() {
-            error("Failed to set permissions on", Some(dir));
-        } else if verbose {
-            //TODO:
-            println!("\x1b[1;32mSet permissions {:o} on {}\x1b[0m", mode, dir);
-        }
+/// Give `dir` a mode, worked out from its current one if symbolic.
+pub fn set_permissions(dir: &Path, mode: &Mode) -> Result<(), DrakoError> {
+    let metadata = fs::metadata(dir).map_err(|e| DrakoError::io(dir, e))?;
+    let mut perms = metadata.permissions();
+    perms.set_mode(mode.apply(perms.mode(), metadata.is_dir()) & 0o7777);
+    fs::set_permissions(dir, perms).map_err(|e| DrakoError::io(dir, e))
+}
+
+/// Give `dir` a default ACL with the owner, group and other permissions it
+/// has now, so everything created in it later gets them whatever the umask.
+/// Returns `false` if the file system has no ACLs.
+pub fn set_default_acl(dir: &Path) -> Result<bool, DrakoError> {
+    let mode = fs::metadata(dir)
+        .map_err(|e| DrakoError::io(dir, e))?
+        .permissions()
+        .mode();
+    match xattr::set_default_acl(dir, mode >> 6, mode >> 3, mode) {
+        Ok(()) => Ok(true),
+        Err(e) if xattr::is_unsupported(&e) => Ok(false),
+        Err(e) => Err(DrakoError::io(dir, e)),
     }
 }
 
-pub fn process_flags(dir: &str, flags: &[String], verbose: bool) {
-    for flag in flags {
-        match flag.as_str() {
-            "--git" | "-g" => run_command(dir, "git init", verbose),
-            "--npm" | "-n" => run_command(dir, "npm init -y", verbose),
-            "--bun" | "-b" => run_command(dir, "bun init", verbose),
-            "--yarn" | "-y" => run_command(dir, "yarn init -y", verbose),
-            "--pnpm" | "-p" => run_command(dir, "pnpm init", verbose),
-            "--cargo" | "-c" => run_command(dir, "cargo init", verbose),
-            "--go" | "-go" => run_command(dir, &format!("go mod init {}", dir), verbose),
-            "--readme" => {
-                if create_readme(dir, verbose).is_err() {
-                    error("Failed to create README.md in", Some(dir));
-                }
-            }
-            "--docker" => {
-                if create_dockerfile(dir, verbose).is_err() {
-                    error("Failed to create Dockerfile in", Some(dir));
-                }
-            }
-            "--mit" => {
-                if create_mitfile(dir, verbose).is_err() {
-                    error("Failed to create LICENSE in", Some(dir));
-                }
-            }
-            _ => eprintln!("Unknown flag: {}", flag),
+/// Rename the staged project at `staging` to `path` and remove the
+/// now-empty staging directory around it.
+pub fn commit_staged(staging: &Path, path: &Path) -> Result<(), DrakoError> {
+    if path.exists() {
+        return Err(DrakoError::AlreadyExists(path.to_path_buf()));
+    }
+    fs::rename(staging, path).map_err(|e| DrakoError::io(path, e))?;
+    match staging.parent() {
+        Some(outer) => fs::remove_dir(outer).map_err(|e| DrakoError::io(outer, e)),
+        None => Ok(()),
+    }
+}
+
+/// Remove everything under `staging`, then each of `parents` (innermost
+/// first) that is empty.
+pub fn roll_back(staging: &Path, parents: &[PathBuf]) -> Result<(), DrakoError> {
+    let result = match fs::remove_dir_all(staging) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(DrakoError::io(staging, e)),
+        _ => Ok(()),
+    };
+    for parent in parents {
+        // Someone else may have put something there in the meantime.
+        let _ = fs::remove_dir(parent);
+    }
+    result
+}
+
+/// Change the owner and/or group of `path`.
+pub fn set_owner(path: &Path, owner: &Owner) -> Result<(), DrakoError> {
+    std::os::unix::fs::chown(path, owner.uid(), owner.gid()).map_err(|e| DrakoError::io(path, e))
+}
+
+/// Everything inside `dir`, without following symbolic links.
+fn contents(dir: &Path, paths: &mut HashSet<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            contents(&path, paths);
         }
+        paths.insert(path);
     }
 }
 
-pub fn run_command(dir: &str, cmd: &str, verbose: bool) {
+/// Like [`run_command`], then give everything the command created in `dir`
+/// to `owner`. What was there before keeps its owner.
+pub fn run_command_as(dir: &Path, cmd: &str, owner: &Owner) -> Result<String, DrakoError> {
+    let mut before = HashSet::new();
+    contents(dir, &mut before);
+    let result = run_command(dir, cmd);
+    let mut after = HashSet::new();
+    contents(dir, &mut after);
+    for path in after.difference(&before) {
+        std::os::unix::fs::lchown(path, owner.uid(), owner.gid())
+            .map_err(|e| DrakoError::io(path, e))?;
+    }
+    result
+}
+
+/// Set the extended attribute `name` of `path`.
+pub fn set_xattr(path: &Path, name: &str, value: &str) -> Result<(), DrakoError> {
+    xattr::set(path, name, value.as_bytes()).map_err(|e| DrakoError::Io {
+        path: path.to_path_buf(),
+        message: format!("the file system rejected {}: {}", name, e),
+    })
+}
+
+/// Run the project flags for a directory that already exists.
+pub fn process_flags(
+    dir: &Path,
+    flags: &[&FlagSpec],
+    conflicts: &ConflictRules,
+) -> Vec<StepResult> {
+    flag_steps(dir, flags, conflicts)
+        .into_iter()
+        .map(|step| {
+            let outcome = run_step(&step);
+            StepResult { step, outcome }
+        })
+        .collect()
+}
+
+/// Run `cmd` with `sh -c` in `dir`, returning what it wrote to stderr.
+pub fn run_command(dir: &Path, cmd: &str) -> Result<String, DrakoError> {
     let output = Command::new("sh")
         .arg("-c")
         .arg(cmd)
         .current_dir(dir)
-        .output();
+        .output()
+        .map_err(|e| DrakoError::io(dir, e))?;
 
-    match output {
-        Ok(output) if output.status.success() => {
-            if verbose {
-                //TODO:
-                println!("\x1b[1;32mRan:\x1b[0m {} in {}", cmd, dir);
-            }
-        }
-        Ok(output) => {
-            //TODO:
-            eprintln!(
-                "\x1b[1;31mFailed:\x1b[0m {} in {}\n{}",
-                cmd,
-                dir,
-                String::from_utf8_lossy(&output.stderr)
-            );
-        }
-        Err(e) => {
-            //TODO:
-            eprintln!("\x1b[1;31mError running {} in {}:\x1b[0m {}", cmd, dir, e);
-        }
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stderr).to_string()),
+        Some(COMMAND_NOT_FOUND) => Err(DrakoError::ToolMissing(
+            cmd.split_whitespace().next().unwrap_or(cmd).to_string(),
+        )),
+        status => Err(DrakoError::CommandFailed {
+            command: cmd.to_string(),
+            dir: dir.to_path_buf(),
+            status,
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }),
     }
 }
@@ -0,0 +1,97 @@
+//! Extended attributes, and the default POSIX ACLs stored in them.
+
+use std::io;
+use std::path::Path;
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+use std::{ffi::c_void, ffi::CString, os::unix::ffi::OsStrExt};
+
+/// Parse a `--xattr` value such as `user.project=atlas`.
+pub fn parse(spec: &str) -> Result<(String, String), String> {
+    match spec.split_once('=') {
+        Some((name, value)) if name.contains('.') && !name.starts_with('.') => {
+            Ok((name.to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "Invalid extended attribute: {} (expected namespace.name=value, as in user.project=atlas)",
+            spec
+        )),
+    }
+}
+
+/// Set the attribute `name` of `path` to `value`.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+pub fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let c_name = CString::new(name)?;
+    let value_ptr = value.as_ptr() as *const c_void;
+    // SAFETY: the strings are NUL-terminated and `value` is valid for its length.
+    #[cfg(not(target_os = "macos"))]
+    let status =
+        unsafe { libc::setxattr(c_path.as_ptr(), c_name.as_ptr(), value_ptr, value.len(), 0) };
+    // macOS takes an extra offset for resource forks.
+    #[cfg(target_os = "macos")]
+    let status = unsafe {
+        libc::setxattr(
+            c_path.as_ptr(),
+            c_name.as_ptr(),
+            value_ptr,
+            value.len(),
+            0,
+            0,
+        )
+    };
+    match status {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Set the attribute `name` of `path` to `value`: not implemented here.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+pub fn set(_path: &Path, _name: &str, _value: &[u8]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "extended attributes are not supported on this system",
+    ))
+}
+
+/// Give the directory `path` a default ACL, so everything created in it later
+/// gets these permissions (`rwx` bits) for its owner, group and others,
+/// whatever the umask of the process creating it.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_default_acl(path: &Path, user: u32, group: u32, other: u32) -> io::Result<()> {
+    /// Attribute holding a directory's default ACL on Linux.
+    const DEFAULT_ACL: &str = "system.posix_acl_default";
+    const ACL_EA_VERSION: u32 = 2;
+    const ACL_USER_OBJ: u16 = 0x01;
+    const ACL_GROUP_OBJ: u16 = 0x04;
+    const ACL_OTHER: u16 = 0x20;
+    const ACL_UNDEFINED_ID: u32 = u32::MAX;
+
+    let mut value = ACL_EA_VERSION.to_le_bytes().to_vec();
+    for (tag, perms) in [
+        (ACL_USER_OBJ, user),
+        (ACL_GROUP_OBJ, group),
+        (ACL_OTHER, other),
+    ] {
+        value.extend(tag.to_le_bytes());
+        value.extend((perms as u16 & 0o7).to_le_bytes());
+        value.extend(ACL_UNDEFINED_ID.to_le_bytes());
+    }
+    set(path, DEFAULT_ACL, &value)
+}
+
+/// Give the directory `path` a default ACL: only Linux stores them as
+/// extended attributes, so elsewhere this is unsupported.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn set_default_acl(_path: &Path, _user: u32, _group: u32, _other: u32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "default ACLs are only supported on Linux",
+    ))
+}
+
+/// Whether an error means the file system has no extended attributes or ACLs.
+pub fn is_unsupported(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::Unsupported || err.raw_os_error() == Some(libc::EOPNOTSUPP)
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::run_drako;
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(dir: &std::path::Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_atomic_success_moves_project_into_place() {
+        let temp_dir = tempdir().unwrap();
+        let tree = format!("{}/svc(src,tests)", temp_dir.path().display());
+
+        let (code, stdout, stderr) =
+            run_drako(&[&tree, "-r", "-750", "--atomic", "--output", "json"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+
+        let svc = temp_dir.path().join("svc");
+        assert_eq!(names(temp_dir.path()), ["svc"]);
+        assert_eq!(names(&svc), ["README.md", "src", "tests"]);
+        // Steps report the final paths, not the staging directory.
+        assert!(stdout.contains(&format!(
+            r#"{{"action":"write_file","path":"{}/README.md","status":"done"}}"#,
+            svc.display()
+        )));
+        assert!(stdout.contains(r#"{"action":"commit""#), "{}", stdout);
+    }
+
+    #[test]
+    fn test_atomic_failure_removes_everything() {
+        let temp_dir = tempdir().unwrap();
+        // The README.md directory gets in the way of the README.md file.
+        let tree = format!("{}/a/b/svc(src,README.md)", temp_dir.path().display());
+
+        let (code, _, stderr) = run_drako(&[&tree, "-r", "--atomic"]);
+        assert_eq!(code, Some(9), "{}", stderr);
+        assert!(
+            stderr.contains("Removed the unfinished project"),
+            "{}",
+            stderr
+        );
+        assert!(names(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_atomic_keeps_existing_directories() {
+        let temp_dir = tempdir().unwrap();
+        let svc = temp_dir.path().join("svc");
+        fs::create_dir(&svc).unwrap();
+        fs::write(svc.join("notes.txt"), "keep me").unwrap();
+
+        let (code, _, _) = run_drako(&[svc.to_str().unwrap(), "-r", "--atomic", "--exist-ok"]);
+        assert_eq!(code, Some(3));
+        assert_eq!(names(temp_dir.path()), ["svc"]);
+        assert_eq!(names(&svc), ["notes.txt"]);
+    }
+
+    #[test]
+    fn test_atomic_is_only_for_new_directories() {
+        let temp_dir = tempdir().unwrap();
+
+        let (code, _, stderr) = run_drako(&["add", temp_dir.path().to_str().unwrap(), "--atomic"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("--atomic only works for new directories"));
+    }
+}
@@ -0,0 +1,57 @@
+//! What the integration tests share.
+#![allow(dead_code)]
+
+use std::ops::{Deref, DerefMut};
+use std::process::Command;
+use tempfile::{tempdir, TempDir};
+
+/// `cargo run --` for drako. It reads no config file and keeps its journal in
+/// a temporary directory of its own, so tests never touch the user's; set
+/// `XDG_STATE_HOME` to share a journal between runs.
+pub struct Drako {
+    command: Command,
+    _state: TempDir,
+}
+
+pub fn drako() -> Drako {
+    let state = tempdir().unwrap();
+    let mut command = Command::new("cargo");
+    command
+        .arg("run")
+        .arg("--")
+        .env("DRAKO_CONFIG", "/nonexistent/drako/config")
+        .env("XDG_STATE_HOME", state.path());
+    Drako {
+        command,
+        _state: state,
+    }
+}
+
+impl Deref for Drako {
+    type Target = Command;
+
+    fn deref(&self) -> &Command {
+        &self.command
+    }
+}
+
+impl DerefMut for Drako {
+    fn deref_mut(&mut self) -> &mut Command {
+        &mut self.command
+    }
+}
+
+/// Run `command`: its exit code, stdout and stderr.
+pub fn output(command: &mut Command) -> (Option<i32>, String, String) {
+    let output = command.output().expect("Failed to execute drako");
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    )
+}
+
+/// Run drako with `args`: its exit code, stdout and stderr.
+pub fn run_drako(args: &[&str]) -> (Option<i32>, String, String) {
+    output(drako().args(args))
+}
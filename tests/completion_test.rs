@@ -0,0 +1,110 @@
+mod common;
+
+use common::drako;
+use drako::cli::SUBCOMMANDS;
+use drako::completion::{generate, Shell};
+use drako::flags::FLAGS;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_shell_lists_every_flag() {
+        for shell in Shell::ALL {
+            let script = generate(shell);
+            for spec in FLAGS {
+                assert!(
+                    script.contains(spec.long),
+                    "{} completion should mention --{}",
+                    shell,
+                    spec.long
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_shell_completes_subcommands() {
+        for shell in Shell::ALL {
+            let script = generate(shell);
+            let names: Vec<&str> = SUBCOMMANDS.iter().map(|sub| sub.name).collect();
+            for sub in SUBCOMMANDS {
+                // Bash has no room for descriptions.
+                let offered = match shell {
+                    Shell::Bash => script.contains(&format!("\"{}", names.join(" "))),
+                    _ => script.contains(sub.help),
+                };
+                assert!(offered, "{} completion should offer {}", shell, sub.name);
+            }
+            assert!(script.contains("show"), "{} after config", shell);
+            assert!(script.contains("parents-mode"), "{} after help", shell);
+        }
+    }
+
+    #[test]
+    fn test_bash_completes_words_after_subcommands() {
+        let script = generate(Shell::Bash);
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(format!(
+                "{}\nCOMP_WORDS=(drako un); COMP_CWORD=1; _drako; echo \"${{COMPREPLY[*]}}\"\n\
+                 COMP_WORDS=(drako help parents); COMP_CWORD=2; _drako; echo \"${{COMPREPLY[*]}}\"",
+                script
+            ))
+            .current_dir(std::env::temp_dir())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines[0].split_whitespace().next(),
+            Some("undo"),
+            "{}",
+            stdout
+        );
+        assert_eq!(lines[1], "parents-mode");
+    }
+
+    #[test]
+    fn test_mode_hints_are_offered() {
+        assert!(generate(Shell::Bash).contains("-755"));
+        assert!(generate(Shell::Zsh).contains("'-700[Set directory permissions to 700]'"));
+        assert!(generate(Shell::Fish).contains("complete -c drako -o 750"));
+        assert!(generate(Shell::PowerShell).contains("@('-775',"));
+    }
+
+    #[test]
+    fn test_bash_script_is_valid_syntax() {
+        let mut child = Command::new("bash")
+            .arg("-n")
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(generate(Shell::Bash).as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_completion_flag_prints_script() {
+        let output = drako().args(["--completion", "fish"]).output().unwrap();
+
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("complete -c drako -l git"));
+    }
+
+    #[test]
+    fn test_unsupported_shell() {
+        let output = drako().args(["--completion", "tcsh"]).output().unwrap();
+
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unsupported shell"));
+    }
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::{drako, output};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_drako(args: &[&str], config: &Path) -> (Option<i32>, String, String) {
+        output(drako().args(args).env("DRAKO_CONFIG", config))
+    }
+
+    /// A directory holding a hand-written README.md.
+    fn existing_project(root: &Path) -> String {
+        let dir = root.join("project");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("README.md"), "mine\n").unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_exist_ok_sets_up_existing_directory() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("project");
+        fs::create_dir(&dir).unwrap();
+        let config = temp_dir.path().join("config");
+
+        let (code, _, stderr) =
+            run_drako(&[dir.to_str().unwrap(), "--exist-ok", "--license"], &config);
+
+        assert_eq!(code, Some(0));
+        assert!(!stderr.contains("already exists"));
+        assert!(dir.join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_existing_file_fails_by_default() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        let config = temp_dir.path().join("config");
+
+        let (code, _, stderr) = run_drako(&["add", &dir, "--readme", "--license"], &config);
+
+        assert_eq!(code, Some(9));
+        assert!(stderr.contains("File already exists"));
+        assert_eq!(
+            fs::read_to_string(Path::new(&dir).join("README.md")).unwrap(),
+            "mine\n"
+        );
+        assert!(Path::new(&dir).join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_conflict_skip_and_overwrite() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        let config = temp_dir.path().join("config");
+        let readme = Path::new(&dir).join("README.md");
+
+        let (code, _, _) = run_drako(&["add", &dir, "-r", "--conflict", "skip"], &config);
+        assert_eq!(code, Some(0));
+        assert_eq!(fs::read_to_string(&readme).unwrap(), "mine\n");
+
+        let (code, _, _) = run_drako(&["add", &dir, "-r", "--conflict=overwrite"], &config);
+        assert_eq!(code, Some(0));
+        assert_ne!(fs::read_to_string(&readme).unwrap(), "mine\n");
+    }
+
+    #[test]
+    fn test_conflict_backup_keeps_old_file() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        let config = temp_dir.path().join("config");
+
+        let (code, _, _) = run_drako(&["add", &dir, "-r", "--conflict", "backup"], &config);
+
+        assert_eq!(code, Some(0));
+        let dir = Path::new(&dir);
+        assert_eq!(
+            fs::read_to_string(dir.join("README.md.bak")).unwrap(),
+            "mine\n"
+        );
+        assert_ne!(fs::read_to_string(dir.join("README.md")).unwrap(), "mine\n");
+    }
+
+    #[test]
+    fn test_per_file_policy_overrides_default() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        fs::write(Path::new(&dir).join("LICENSE"), "old\n").unwrap();
+        let config = temp_dir.path().join("config");
+
+        let (code, _, _) = run_drako(
+            &[
+                "add",
+                &dir,
+                "-r",
+                "-l",
+                "--conflict",
+                "README.md=skip",
+                "--conflict",
+                "overwrite",
+            ],
+            &config,
+        );
+
+        assert_eq!(code, Some(0));
+        let dir = Path::new(&dir);
+        assert_eq!(fs::read_to_string(dir.join("README.md")).unwrap(), "mine\n");
+        assert_ne!(fs::read_to_string(dir.join("LICENSE")).unwrap(), "old\n");
+    }
+
+    #[test]
+    fn test_prompt_without_terminal_fails() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        let config = temp_dir.path().join("config");
+
+        let (code, _, _) = run_drako(&["add", &dir, "-r", "--conflict", "prompt"], &config);
+
+        assert_eq!(code, Some(9));
+        assert_eq!(
+            fs::read_to_string(Path::new(&dir).join("README.md")).unwrap(),
+            "mine\n"
+        );
+    }
+
+    #[test]
+    fn test_conflict_policy_from_config() {
+        let temp_dir = tempdir().unwrap();
+        let dir = existing_project(temp_dir.path());
+        let config = temp_dir.path().join("config");
+        fs::write(&config, "conflict = backup\n").unwrap();
+
+        let (code, _, _) = run_drako(&["add", &dir, "-r"], &config);
+
+        assert_eq!(code, Some(0));
+        assert!(Path::new(&dir).join("README.md.bak").exists());
+    }
+
+    #[test]
+    fn test_invalid_conflict_policy() {
+        let temp_dir = tempdir().unwrap();
+        let config = temp_dir.path().join("config");
+        let dir = temp_dir.path().join("project");
+
+        let (code, _, stderr) =
+            run_drako(&[dir.to_str().unwrap(), "--conflict", "sometimes"], &config);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("sometimes"));
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::run_drako;
+use drako::docs;
+use drako::flags::{self, FLAGS};
+use std::fs;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readme_table_matches_flags() {
+        let readme = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md")).unwrap();
+
+        assert!(
+            readme.contains(&docs::markdown_table()),
+            "README options table is out of date; regenerate it from docs::markdown_table()"
+        );
+    }
+
+    #[test]
+    fn test_man_page_documents_every_flag() {
+        let page = docs::man_page();
+
+        assert!(page.starts_with(".TH DRAKO 1"));
+        for spec in FLAGS {
+            assert!(
+                page.contains(&format!("\\-\\-{}", spec.long.replace('-', "\\-"))),
+                "man page should document --{}",
+                spec.long
+            );
+        }
+        assert!(page.contains(".SH EXIT STATUS"));
+    }
+
+    #[test]
+    fn test_topic_lists_commands_and_files() {
+        let go = docs::topic(flags::find("go").unwrap());
+        assert!(go.contains("go mod init <dir>"));
+        assert!(go.contains("drako myproject -go"));
+
+        let readme = docs::topic(flags::find("r").unwrap());
+        assert!(readme.contains("<dir>/README.md"));
+    }
+
+    #[test]
+    fn test_help_subcommand() {
+        let (code, _, stderr) = run_drako(&["help", "cargo"]);
+
+        assert_eq!(code, Some(0));
+        assert!(stderr.contains("cargo init"));
+    }
+
+    #[test]
+    fn test_help_unknown_topic() {
+        let (code, _, stderr) = run_drako(&["help", "nope"]);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("No help topic"));
+    }
+
+    #[test]
+    fn test_man_flag() {
+        let (code, stdout, _) = run_drako(&["--man"]);
+
+        assert_eq!(code, Some(0));
+        assert!(stdout.starts_with(".TH DRAKO 1"));
+    }
+}
@@ -1,6 +1,8 @@
+mod common;
+
+use common::drako;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
-use std::process::Command;
 use tempfile::tempdir;
 
 #[cfg(test)]
@@ -12,9 +14,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("test_dir");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .status()
             .unwrap();
@@ -30,9 +30,7 @@ mod tests {
         let dir1 = temp_dir.path().join("dir1");
         let dir2 = temp_dir.path().join("dir2");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(dir1.to_str().unwrap())
             .arg(dir2.to_str().unwrap())
             .status()
@@ -48,9 +46,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let nested_dir = temp_dir.path().join("parent/child/grandchild");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(nested_dir.to_str().unwrap())
             .status()
             .unwrap();
@@ -64,9 +60,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("git_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--git")
             .status()
@@ -81,9 +75,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("readme_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--readme")
             .status()
@@ -100,9 +92,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("license_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--license")
             .status()
@@ -119,9 +109,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("docker_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--docker")
             .status()
@@ -138,21 +126,20 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("invalid_flag_test");
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--invalid-flag")
             .output()
             .unwrap();
 
-        assert!(test_dir.exists()); // Directory should still be created
+        assert_eq!(output.status.code(), Some(2));
+        assert!(!test_dir.exists()); // Nothing is created
         assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown flag"));
     }
 
     #[test]
     fn test_no_arguments() {
-        let output = Command::new("cargo").arg("run").output().unwrap();
+        let output = drako().output().unwrap();
 
         assert!(!output.status.success());
         assert!(String::from_utf8_lossy(&output.stderr).contains("Usage:"));
@@ -163,9 +150,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("multi_flag_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--readme")
             .arg("--license")
@@ -184,9 +169,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("test dir with spaces");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .status()
             .unwrap();
@@ -200,9 +183,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("test-dir_with_special_chars");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .status()
             .unwrap();
@@ -217,14 +198,13 @@ mod tests {
         let test_dir = temp_dir.path().join("existing_dir");
         fs::create_dir(&test_dir).unwrap();
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .status()
             .unwrap();
 
-        assert!(status.success());
+        // An existing directory is reported with its own exit code
+        assert_eq!(status.code(), Some(3));
         assert!(test_dir.exists());
     }
 
@@ -233,9 +213,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("perm_test");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-700")
             .status()
@@ -256,9 +234,7 @@ mod tests {
         let dir1 = temp_dir.path().join("dir1");
         let dir2 = temp_dir.path().join("dir2");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(dir1.to_str().unwrap())
             .arg(dir2.to_str().unwrap())
             .arg("-755")
@@ -281,9 +257,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("invalid_perm_test");
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-9999") // Invalid octal value
             .output()
@@ -302,9 +276,7 @@ mod tests {
         let test_dir = temp_dir.path().join("perm_with_flags_test");
 
         // Use 755 permissions instead of 600 to allow writing to the directory
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-755")
             .arg("--readme")
@@ -329,9 +301,7 @@ mod tests {
         let test_dir = temp_dir.path().join("edge_perm_test");
 
         // Test with 000 permissions (no access)
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-000")
             .status()
@@ -349,9 +319,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let nested_dir = temp_dir.path().join("parent/child/grandchild");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(nested_dir.to_str().unwrap())
             .arg("-750")
             .status()
@@ -375,9 +343,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("non_octal_test");
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-89a") // Not a valid octal number
             .output()
@@ -395,9 +361,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let test_dir = temp_dir.path().join("special_chars_dir");
 
-        let status = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let status = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-777")
             .status()
@@ -409,4 +373,33 @@ mod tests {
         let mode = fs::metadata(&test_dir).unwrap().permissions().mode() & 0o777;
         assert_eq!(mode, 0o777);
     }
+
+    #[test]
+    fn test_exit_code_for_usage_error() {
+        let output = drako()
+            .arg("--mode")
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    #[test]
+    fn test_exit_code_for_partial_failure() {
+        let temp_dir = tempdir().unwrap();
+
+        // A second project whose directory can't be created still lets the first finish
+        let blocker = temp_dir.path().join("blocker");
+        fs::write(&blocker, "").unwrap();
+
+        let output = drako()
+            .arg(blocker.join("child").to_str().unwrap())
+            .arg(temp_dir.path().join("ok_dir").to_str().unwrap())
+            .arg("--readme")
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(temp_dir.path().join("ok_dir/README.md").exists());
+    }
 }
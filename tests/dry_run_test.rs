@@ -0,0 +1,76 @@
+mod common;
+
+use common::run_drako;
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_prints_plan() {
+        let temp_dir = tempdir().unwrap();
+        let parent = temp_dir.path().join("services");
+        let test_dir = parent.join("api");
+
+        let (code, stdout, _) = run_drako(&[
+            test_dir.to_str().unwrap(),
+            "--git",
+            "--readme",
+            "-750",
+            "--dry-run",
+        ]);
+
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains(&format!("create directory {}", parent.display())));
+        assert!(stdout.contains(&format!("create directory {}", test_dir.display())));
+        assert!(stdout.contains("set permissions 750"));
+        assert!(stdout.contains("run `git init`"));
+        assert!(stdout.contains("README.md"));
+        assert!(!parent.exists(), "--dry-run should not touch the disk");
+    }
+
+    #[test]
+    fn test_dry_run_reports_conflicts() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("project");
+        fs::create_dir(&test_dir).unwrap();
+        fs::write(test_dir.join("README.md"), "mine\n").unwrap();
+
+        let (code, _, stderr) = run_drako(&["add", test_dir.to_str().unwrap(), "-r", "--dry-run"]);
+        assert_eq!(code, Some(9));
+        assert!(stderr.contains("File already exists"));
+
+        let (code, stdout, _) = run_drako(&[
+            "add",
+            test_dir.to_str().unwrap(),
+            "-r",
+            "--conflict",
+            "backup",
+            "--dry-run",
+        ]);
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("README.md.bak"));
+        assert!(!test_dir.join("README.md.bak").exists());
+        assert_eq!(
+            fs::read_to_string(test_dir.join("README.md")).unwrap(),
+            "mine\n"
+        );
+    }
+
+    #[test]
+    fn test_dry_run_lists_shared_parents_once() {
+        let temp_dir = tempdir().unwrap();
+        let dirs = format!("{}/a/b{{1..2}}", temp_dir.path().display());
+
+        let (code, stdout, _) = run_drako(&[&dirs, "--dry-run"]);
+        assert_eq!(code, Some(0));
+        let parent = format!(
+            "Would create directory {}/a\u{1b}",
+            temp_dir.path().display()
+        );
+        assert_eq!(stdout.matches(&parent).count(), 1, "{}", stdout);
+        assert!(stdout.contains("/a/b2"));
+    }
+}
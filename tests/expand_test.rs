@@ -0,0 +1,96 @@
+mod common;
+
+use common::run_drako;
+use drako::expand::{expand, MAX_NAMES};
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_lists_and_ranges() {
+        assert_eq!(
+            expand("proj/{src,tests}/{unit,e2e}").unwrap(),
+            [
+                "proj/src/unit",
+                "proj/src/e2e",
+                "proj/tests/unit",
+                "proj/tests/e2e"
+            ]
+        );
+        assert_eq!(
+            expand("day{08..11}").unwrap(),
+            ["day08", "day09", "day10", "day11"]
+        );
+        assert_eq!(expand("{a..c}").unwrap(), ["a", "b", "c"]);
+        assert_eq!(expand("v{3..1}").unwrap(), ["v3", "v2", "v1"]);
+        assert_eq!(expand("{0..20..10}").unwrap(), ["0", "10", "20"]);
+        assert_eq!(expand("x{a,b{1,2}}").unwrap(), ["xa", "xb1", "xb2"]);
+    }
+
+    #[test]
+    fn test_expand_leaves_other_braces_alone() {
+        assert_eq!(expand("plain").unwrap(), ["plain"]);
+        assert_eq!(expand("{}").unwrap(), ["{}"]);
+        assert_eq!(expand("{x}").unwrap(), ["{x}"]);
+        assert_eq!(expand("{1..z}").unwrap(), ["{1..z}"]);
+        assert_eq!(expand("open{a,b").unwrap(), ["open{a,b"]);
+        assert_eq!(expand("{x}{1,2}").unwrap(), ["{x}1", "{x}2"]);
+    }
+
+    #[test]
+    fn test_huge_ranges_are_rejected() {
+        assert_eq!(expand("{1..10000}").unwrap().len(), MAX_NAMES);
+        assert!(expand("{1..10001}").is_err());
+        assert!(expand("{1..10000000000}").is_err());
+        assert!(expand("{1..200}{1..200}").is_err());
+        assert_eq!(
+            expand("{-9223372036854775808..9223372036854775807..9223372036854775807}").unwrap(),
+            ["-9223372036854775808", "-1", "9223372036854775806"]
+        );
+
+        let (code, _, stderr) =
+            run_drako(&["x{-9223372036854775808..9223372036854775807}", "--dry-run"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("expands to more than"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_expanded_directories_are_created() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("proj");
+        let arg = format!("{}/{{src,tests}}/{{unit,e2e}}", root.display());
+
+        let (code, _, _) = run_drako(&[&arg]);
+
+        assert_eq!(code, Some(0));
+        for dir in ["src/unit", "src/e2e", "tests/unit", "tests/e2e"] {
+            assert!(root.join(dir).is_dir(), "{} should exist", dir);
+        }
+    }
+
+    #[test]
+    fn test_no_expand_keeps_names_literal() {
+        let temp_dir = tempdir().unwrap();
+        let arg = format!("{}/day{{01..03}}", temp_dir.path().display());
+
+        let (code, _, _) = run_drako(&[&arg, "--no-expand"]);
+
+        assert_eq!(code, Some(0));
+        assert!(temp_dir.path().join("day{01..03}").is_dir());
+        assert!(!temp_dir.path().join("day01").exists());
+    }
+
+    #[test]
+    fn test_no_expand_keeps_trees_literal() {
+        let temp_dir = tempdir().unwrap();
+        let arg = format!("{}/Notes(old)", temp_dir.path().display());
+
+        let (code, _, stderr) = run_drako(&[&arg, "--no-expand"]);
+
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(temp_dir.path().join("Notes(old)").is_dir());
+        assert!(!temp_dir.path().join("Notes").exists());
+    }
+}
@@ -1,23 +1,15 @@
+mod common;
+
 #[cfg(test)]
 mod flag_parsing_tests {
     use std::fs;
-    use std::process::Command;
     use tempfile::tempdir;
 
     // Helper function to run the drako binary with specific arguments
     fn run_drako(args: &[&str]) -> (bool, String, String) {
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
-            .args(args)
-            .output()
-            .expect("Failed to execute drako");
-
-        let success = output.status.success();
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let (code, stdout, stderr) = super::common::run_drako(args);
 
-        (success, stdout, stderr)
+        (code == Some(0), stdout, stderr)
     }
 
     #[test]
@@ -130,15 +122,12 @@ mod flag_parsing_tests {
 
         let (success, _, stderr) = run_drako(&[test_dir_str, "-9999"]);
 
-        assert!(
-            success,
-            "Command should still succeed even with invalid permission"
-        );
+        assert!(!success, "Command should fail with an invalid permission");
         assert!(
             stderr.contains("Invalid permission format"),
             "Should show invalid permission error"
         );
-        assert!(test_dir.exists(), "Directory should still be created");
+        assert!(!test_dir.exists(), "Directory should not be created");
     }
 
     #[test]
@@ -149,12 +138,25 @@ mod flag_parsing_tests {
 
         let (success, _, stderr) = run_drako(&[test_dir_str, "--nonexistent-flag"]);
 
-        assert!(success, "Command should still succeed");
+        assert!(!success, "Command should fail");
         assert!(
             stderr.contains("Unknown flag"),
             "Should show unknown flag error"
         );
-        assert!(test_dir.exists(), "Directory should still be created");
+        assert!(!test_dir.exists(), "Directory should not be created");
+    }
+
+    #[test]
+    fn test_unknown_letter_in_bundle() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_unknown_bundle");
+        let test_dir_str = test_dir.to_str().unwrap();
+
+        let (success, _, stderr) = run_drako(&[test_dir_str, "-gx"]);
+
+        assert!(!success, "Command should fail");
+        assert!(stderr.contains("Unknown flag: -gx"), "Should name the bundle");
+        assert!(!test_dir.exists(), "Directory should not be created");
     }
 
     #[test]
@@ -163,11 +165,12 @@ mod flag_parsing_tests {
         let test_dir = temp_dir.path().join("test_dash_only");
         let test_dir_str = test_dir.to_str().unwrap();
 
-        let (success, _, _) = run_drako(&[test_dir_str, "-"]);
+        let (success, _, stderr) = run_drako(&[test_dir_str, "-"]);
 
-        assert!(success, "Command should succeed");
-        assert!(test_dir.exists(), "Directory should be created");
-        // The "-" should be treated as a flag but not recognized, so it should show an unknown flag error
+        // The "-" is treated as a flag but not recognized, so it is an unknown flag error
+        assert!(!success, "Command should fail");
+        assert!(stderr.contains("Unknown flag"), "Should show unknown flag error");
+        assert!(!test_dir.exists(), "Directory should not be created");
     }
 
     #[test]
@@ -212,7 +215,7 @@ mod flag_parsing_tests {
 
         let (success, stdout, _) = run_drako(&[test_dir.to_str().unwrap(), "-v"]);
 
-        assert!(success, "Command should succeed");
+        assert!(!success, "Command should report the existing directory");
         assert!(
             stdout.contains("Directory already exists"),
             "Should indicate directory exists"
@@ -226,5 +229,127 @@ mod flag_parsing_tests {
         assert!(!success, "Command should fail with no arguments");
         assert!(stderr.contains("Usage:"), "Should show usage information");
     }
-}
 
+    #[test]
+    fn test_advertised_short_flags() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_advertised_short");
+        let test_dir_str = test_dir.to_str().unwrap();
+
+        let (success, _, stderr) = run_drako(&[test_dir_str, "-r", "-l", "-do"]);
+
+        assert!(success, "Command should succeed");
+        assert!(
+            !stderr.contains("Unknown flag"),
+            "Advertised flags should be recognized"
+        );
+        assert!(test_dir.join("README.md").exists());
+        assert!(test_dir.join("LICENSE").exists());
+        assert!(test_dir.join("Dockerfile").exists());
+    }
+
+    #[test]
+    fn test_mit_alias() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_mit_alias");
+        let test_dir_str = test_dir.to_str().unwrap();
+
+        let (success, _, stderr) = run_drako(&[test_dir_str, "--mit"]);
+
+        assert!(success, "Command should succeed");
+        assert!(!stderr.contains("Unknown flag"));
+        assert!(test_dir.join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_help_lists_every_option() {
+        let (success, _, stderr) = run_drako(&["--help"]);
+
+        assert!(success, "Help should succeed");
+        for flag in [
+            "--git",
+            "--readme",
+            "--license",
+            "--docker",
+            "--go",
+            "--cargo",
+            "--npm",
+            "--bun",
+            "--yarn",
+            "--pnpm",
+            "--deno",
+            "--mode",
+            "--verbose",
+            "--help",
+            "--version",
+        ] {
+            assert!(stderr.contains(flag), "Help should list {}", flag);
+        }
+    }
+
+    #[test]
+    fn test_bundled_short_flags() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_bundled");
+        let test_dir_str = test_dir.to_str().unwrap();
+
+        let (success, _, stderr) = run_drako(&[test_dir_str, "-rl"]);
+
+        assert!(success, "Command should succeed");
+        assert!(!stderr.contains("Unknown flag"));
+        assert!(test_dir.join("README.md").exists());
+        assert!(test_dir.join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_mode_with_value() {
+        let temp_dir = tempdir().unwrap();
+        let inline_dir = temp_dir.path().join("test_mode_inline");
+        let separate_dir = temp_dir.path().join("test_mode_separate");
+
+        let (success, _, _) = run_drako(&[inline_dir.to_str().unwrap(), "--mode=700"]);
+        assert!(success, "Command should succeed");
+        let (success, _, _) = run_drako(&["--mode", "750", separate_dir.to_str().unwrap()]);
+        assert!(success, "Command should succeed");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&inline_dir).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o700);
+            let mode = fs::metadata(&separate_dir).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o750);
+        }
+    }
+
+    #[test]
+    fn test_missing_option_value() {
+        let (success, _, stderr) = run_drako(&["--mode"]);
+
+        assert!(!success, "Command should fail without a value");
+        assert!(stderr.contains("requires a value"));
+    }
+
+    #[test]
+    fn test_unexpected_option_value() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_unexpected_value");
+
+        let (success, _, stderr) = run_drako(&[test_dir.to_str().unwrap(), "--readme=yes"]);
+
+        assert!(!success, "Command should fail");
+        assert!(stderr.contains("does not take a value"));
+        assert!(!test_dir.exists(), "Nothing should be created");
+    }
+
+    #[test]
+    fn test_double_dash_ends_options() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("test_double_dash");
+
+        let (success, _, _) = run_drako(&["-r", "--", test_dir.to_str().unwrap()]);
+
+        assert!(success, "Command should succeed");
+        assert!(test_dir.join("README.md").exists());
+    }
+}
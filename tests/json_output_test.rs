@@ -0,0 +1,114 @@
+mod common;
+
+use common::run_drako;
+use drako::json::record;
+use drako::plan::{Outcome, Step, StepResult};
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_record_per_step() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("json_project");
+        let path = test_dir.to_str().unwrap();
+
+        let (code, stdout, _) = run_drako(&[path, "--readme", "-750", "--output", "json"]);
+
+        assert_eq!(code, Some(0));
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                format!(
+                    r#"{{"action":"create_dir","path":"{}","status":"done"}}"#,
+                    path
+                ),
+                format!(
+                    r#"{{"action":"set_permissions","path":"{}","mode":"750","status":"done"}}"#,
+                    path
+                ),
+                format!(
+                    r#"{{"action":"write_file","path":"{}/README.md","status":"done"}}"#,
+                    path
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_failures_and_skips() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("existing");
+        fs::create_dir(&test_dir).unwrap();
+
+        let (code, stdout, _) = run_drako(&[test_dir.to_str().unwrap(), "-r", "--output=json"]);
+
+        assert_eq!(code, Some(3));
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""status":"failed""#));
+        assert!(lines[0].contains(r#""exit_code":3"#));
+        assert!(lines[1].contains(r#""status":"skipped""#));
+        assert!(
+            !stdout.contains('\x1b'),
+            "JSON output should not be colored"
+        );
+    }
+
+    #[test]
+    fn test_json_dry_run() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("planned");
+
+        let (code, stdout, _) = run_drako(&[
+            test_dir.to_str().unwrap(),
+            "--git",
+            "--dry-run",
+            "--output",
+            "json",
+        ]);
+
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains(r#""action":"run_command""#));
+        assert!(stdout.contains(r#""command":"git init""#));
+        assert!(stdout.lines().all(|l| l.contains(r#""status":"planned""#)));
+        assert!(!test_dir.exists());
+    }
+
+    #[test]
+    fn test_json_commands_carry_stderr() {
+        let temp_dir = tempdir().unwrap();
+        let stderr = drako::utils::run_command(temp_dir.path(), "echo note >&2").unwrap();
+        assert_eq!(stderr, "note\n");
+
+        let line = record(&StepResult {
+            step: Step::RunCommand {
+                dir: temp_dir.path().to_path_buf(),
+                command: "echo note >&2".to_string(),
+                owner: None,
+            },
+            outcome: Outcome::Ran(stderr),
+        });
+        assert!(
+            line.contains(r#""status":"done","exit_status":0,"stderr":"note\n""#),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn test_unknown_output_format() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("xml");
+
+        let (code, _, stderr) = run_drako(&[test_dir.to_str().unwrap(), "--output", "xml"]);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Unknown output format: xml"));
+        assert!(!test_dir.exists());
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::run_drako;
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_fills_empty_leaf_directories() {
+        let temp_dir = tempdir().unwrap();
+        let app = temp_dir.path().join("app");
+        let tree = format!("{}(src(lib),docs)", app.display());
+
+        let (code, _, stderr) = run_drako(&[&tree, "-r", "--keep"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(app.join("src/lib/.gitkeep").is_file());
+        assert!(app.join("docs/.gitkeep").is_file());
+        // Only leaves, and not where files were generated.
+        assert!(!app.join("src/.gitkeep").exists());
+        assert!(!app.join(".gitkeep").exists());
+        assert_eq!(fs::read(app.join("docs/.gitkeep")).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_keep_name() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("empty");
+
+        let (code, _, stderr) =
+            run_drako(&[dir.to_str().unwrap(), "--keep", "--keep-name", ".keep"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(dir.join(".keep").is_file());
+        assert!(!dir.join(".gitkeep").exists());
+
+        // --keep-name implies --keep.
+        let other = temp_dir.path().join("other");
+        let (code, _, stderr) = run_drako(&[other.to_str().unwrap(), "--keep-name", ".keep"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(other.join(".keep").is_file());
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--keep-name", "../x"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Invalid placeholder name"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_keep_skips_directories_that_are_not_empty() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "").unwrap();
+
+        let (code, stdout, _) = run_drako(&[
+            "add",
+            temp_dir.path().to_str().unwrap(),
+            "--keep",
+            "--output",
+            "json",
+        ]);
+        assert_eq!(code, Some(0));
+        assert!(
+            stdout.contains(r#""action":"keep_file""#) && stdout.contains("not empty"),
+            "{}",
+            stdout
+        );
+        assert!(!temp_dir.path().join(".gitkeep").exists());
+    }
+}
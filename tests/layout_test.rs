@@ -0,0 +1,135 @@
+mod common;
+
+use common::run_drako;
+use drako::layout;
+use drako::{EntryKind, Mode};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    const LAYOUT: &str = "\
+# service layout
+src/
+  bin/          -750
+  main.rs       # an empty file
+docs/           --readme
+scripts         mode=700
+  \"run tests.sh\"  mode=755
+";
+
+    #[test]
+    fn test_parse_layout() {
+        let entries = layout::parse(LAYOUT).unwrap();
+
+        let paths: Vec<PathBuf> = entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                "src",
+                "src/bin",
+                "src/main.rs",
+                "docs",
+                "scripts",
+                "scripts/run tests.sh"
+            ]
+            .map(PathBuf::from)
+        );
+        let kinds: Vec<EntryKind> = entries.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EntryKind::Dir,
+                EntryKind::Dir,
+                EntryKind::File,
+                EntryKind::Dir,
+                EntryKind::Dir,
+                EntryKind::File
+            ]
+        );
+        assert_eq!(entries[1].mode, Some(Mode::Octal(0o750)));
+        assert_eq!(entries[3].flags[0].long, "readme");
+        assert_eq!(entries[5].mode, Some(Mode::Octal(0o755)));
+    }
+
+    #[test]
+    fn test_parse_layout_errors() {
+        let cases = [
+            ("a/\n    b/\n  c\n", 3, "indent"),
+            ("a/\n\tb/\n", 2, "tabs"),
+            ("file.txt --readme\n", 1, "only directories"),
+            ("a/ --nope\n", 1, "not a mode or project flag"),
+            ("../up/\n", 1, "relative path"),
+        ];
+        for (text, line, message) in cases {
+            let (number, error) = layout::parse(text).err().unwrap();
+            assert_eq!(number, line, "{:?}", text);
+            assert!(error.contains(message), "{:?}: {}", text, error);
+        }
+    }
+
+    #[test]
+    fn test_from_tree_creates_layout() {
+        let temp_dir = tempdir().unwrap();
+        let spec = temp_dir.path().join("layout.txt");
+        fs::write(&spec, LAYOUT).unwrap();
+        let svc = temp_dir.path().join("svc");
+
+        let (code, _, _) =
+            run_drako(&[svc.to_str().unwrap(), "--from-tree", spec.to_str().unwrap()]);
+
+        assert_eq!(code, Some(0));
+        assert!(svc.join("src/bin").is_dir());
+        assert!(svc.join("src/main.rs").is_file());
+        assert!(svc.join("docs/README.md").is_file());
+        assert!(svc.join("scripts/run tests.sh").is_file());
+        assert_eq!(mode(&svc.join("src/bin")), 0o750);
+        assert_eq!(mode(&svc.join("scripts")), 0o700);
+        assert_eq!(mode(&svc.join("scripts/run tests.sh")), 0o755);
+    }
+
+    #[test]
+    fn test_invalid_tree_file() {
+        let temp_dir = tempdir().unwrap();
+        let spec = temp_dir.path().join("layout.txt");
+        fs::write(&spec, "src/\n  bin/ -999\n").unwrap();
+        let svc = temp_dir.path().join("svc");
+
+        let (code, _, stderr) =
+            run_drako(&[svc.to_str().unwrap(), "--from-tree", spec.to_str().unwrap()]);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("layout.txt:2:"));
+        assert!(!svc.exists());
+    }
+
+    #[test]
+    fn test_add_fills_in_an_existing_tree() {
+        let temp_dir = tempdir().unwrap();
+        let spec = temp_dir.path().join("layout.txt");
+        fs::write(&spec, LAYOUT).unwrap();
+        let svc = temp_dir.path().join("svc");
+        fs::create_dir_all(svc.join("src")).unwrap();
+        fs::write(svc.join("src/lib.rs"), "// mine\n").unwrap();
+
+        let (code, _, stderr) = run_drako(&[
+            "add",
+            svc.to_str().unwrap(),
+            "--from-tree",
+            spec.to_str().unwrap(),
+        ]);
+
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(svc.join("src/bin").is_dir());
+        assert!(svc.join("src/main.rs").is_file());
+        assert!(svc.join("src/lib.rs").is_file());
+    }
+}
@@ -0,0 +1,181 @@
+use drako::args::{self, ArgOptions};
+use drako::files::ConflictRules;
+use drako::flags;
+use drako::naming::Case;
+use drako::{DrakoError, Entry, Mode, Outcome, Plan, ProjectSpec, Step};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_lists_steps_in_order() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("planned");
+
+        let spec = ProjectSpec {
+            dir: test_dir.to_str().unwrap().to_string(),
+            mode: Some(Mode::Octal(0o750)),
+            flags: vec![
+                flags::lookup("--readme").unwrap(),
+                flags::lookup("--git").unwrap(),
+            ],
+            ..ProjectSpec::default()
+        };
+        let plan = Plan::for_project(&spec);
+
+        assert_eq!(plan.steps.len(), 4);
+        assert_eq!(
+            plan.steps[0],
+            Step::CreateDir {
+                path: test_dir.clone()
+            }
+        );
+        assert!(matches!(
+            plan.steps[1],
+            Step::SetPermissions {
+                mode: Mode::Octal(0o750),
+                ..
+            }
+        ));
+        assert!(
+            matches!(&plan.steps[2], Step::WriteFile { path, .. } if path.ends_with("README.md"))
+        );
+        assert!(
+            matches!(&plan.steps[3], Step::RunCommand { command, .. } if command == "git init")
+        );
+        assert!(!test_dir.exists(), "Planning should not touch the disk");
+    }
+
+    #[test]
+    fn test_execute_reports_each_step() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("executed");
+
+        let mut spec = ProjectSpec::new(test_dir.to_str().unwrap());
+        spec.flags.push(flags::lookup("--license").unwrap());
+        let report = Plan::for_project(&spec).execute(&mut ());
+
+        assert!(report.is_success());
+        assert_eq!(report.results.len(), 2);
+        assert!(test_dir.join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_dry_run_lists_parents_without_touching_disk() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("a").join("b");
+
+        let mut spec = ProjectSpec::new(test_dir.to_str().unwrap());
+        spec.flags.push(flags::lookup("--readme").unwrap());
+        let report = Plan::for_project(&spec).dry_run(&mut (), &mut HashSet::new());
+
+        assert!(report.is_success());
+        assert_eq!(
+            report.results[0].step,
+            Step::CreateDir {
+                path: temp_dir.path().join("a")
+            }
+        );
+        assert_eq!(report.results.len(), 3);
+        assert!(report.results.iter().all(|r| r.outcome == Outcome::Planned));
+        assert!(!temp_dir.path().join("a").exists());
+    }
+
+    #[test]
+    fn test_existing_directory_skips_remaining_steps() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("existing");
+        fs::create_dir(&test_dir).unwrap();
+
+        let mut spec = ProjectSpec::new(test_dir.to_str().unwrap());
+        spec.flags.push(flags::lookup("--readme").unwrap());
+        let report = Plan::for_project(&spec).execute(&mut ());
+
+        assert_eq!(report.exit_code(), 3);
+        assert_eq!(
+            report.results[0].outcome,
+            Outcome::Failed(DrakoError::AlreadyExists(test_dir.clone()))
+        );
+        assert!(matches!(report.results[1].outcome, Outcome::Skipped(_)));
+        assert!(!test_dir.join("README.md").exists());
+    }
+
+    #[test]
+    fn test_process_flags_on_existing_directory() {
+        let temp_dir = tempdir().unwrap();
+
+        let results = drako::process_flags(
+            temp_dir.path(),
+            &[flags::lookup("-do").unwrap()],
+            &ConflictRules::default(),
+        );
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].outcome, Outcome::Done);
+        assert!(temp_dir.path().join("Dockerfile").exists());
+    }
+
+    #[test]
+    fn test_missing_tool_is_reported() {
+        let temp_dir = tempdir().unwrap();
+
+        let result = drako::utils::run_command(temp_dir.path(), "drako-no-such-tool --init");
+
+        assert_eq!(
+            result,
+            Err(DrakoError::ToolMissing("drako-no-such-tool".to_string()))
+        );
+        assert_eq!(result.unwrap_err().exit_code(), 5);
+    }
+
+    #[test]
+    fn test_failed_command_captures_stderr() {
+        let temp_dir = tempdir().unwrap();
+
+        let result = drako::utils::run_command(temp_dir.path(), "echo oops >&2; exit 4");
+
+        match result {
+            Err(DrakoError::CommandFailed { status, stderr, .. }) => {
+                assert_eq!(status, Some(4));
+                assert_eq!(stderr.trim(), "oops");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_args_reads_arguments_like_the_command_line() {
+        let base = ProjectSpec {
+            mode: Some(Mode::Octal(0o750)),
+            entries: vec![Entry::dir("docs")],
+            ..ProjectSpec::default()
+        };
+        let names = vec!["x/{a,b}".to_string(), "My App(Src Code)".to_string()];
+        let options = ArgOptions {
+            no_expand: false,
+            case: Some(Case::Kebab),
+        };
+        let specs = args::expand_args(&names, options, &base).unwrap();
+
+        let dirs: Vec<&str> = specs.iter().map(|spec| spec.dir.as_str()).collect();
+        assert_eq!(dirs, ["x/a", "x/b", "my-app"]);
+        assert_eq!(specs[2].title.as_deref(), Some("My App"));
+        let entries: Vec<PathBuf> = specs[2].entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(entries, [PathBuf::from("src-code"), PathBuf::from("docs")]);
+        assert!(specs
+            .iter()
+            .all(|spec| spec.mode == Some(Mode::Octal(0o750))));
+    }
+
+    #[test]
+    fn test_expand_args_reports_bad_trees() {
+        let names = vec!["app(src".to_string()];
+        let result = args::expand_args(&names, ArgOptions::default(), &ProjectSpec::default());
+        assert!(matches!(result, Err(DrakoError::Usage(_))));
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::run_drako;
+use drako::Mode;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode_of(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o7777
+    }
+
+    fn apply(spec: &str, current: u32, is_dir: bool) -> u32 {
+        spec.parse::<Mode>().unwrap().apply(current, is_dir)
+    }
+
+    #[test]
+    fn test_four_digit_octal() {
+        let temp_dir = tempdir().unwrap();
+        let shared = temp_dir.path().join("shared");
+        let tmp = temp_dir.path().join("tmp");
+
+        let (code, _, _) = run_drako(&[shared.to_str().unwrap(), "-2775"]);
+        assert_eq!(code, Some(0));
+        assert_eq!(mode_of(&shared), 0o2775);
+
+        let (code, _, _) = run_drako(&[tmp.to_str().unwrap(), "--mode", "1777"]);
+        assert_eq!(code, Some(0));
+        assert_eq!(mode_of(&tmp), 0o1777);
+    }
+
+    #[test]
+    fn test_symbolic_mode() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("team");
+
+        let (code, _, _) = run_drako(&[dir.to_str().unwrap(), "--mode=u=rwx,g=rxs,o="]);
+        assert_eq!(code, Some(0));
+        assert_eq!(mode_of(&dir), 0o2750);
+    }
+
+    #[test]
+    fn test_invalid_modes_are_usage_errors() {
+        let temp_dir = tempdir().unwrap();
+
+        for mode in ["-89a", "-12345", "--mode=u=rwz", "--mode=q+x"] {
+            let dir = temp_dir.path().join(mode.trim_start_matches('-'));
+            let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), mode]);
+            assert_eq!(code, Some(2), "{}", mode);
+            assert!(
+                stderr.contains(&format!("Invalid permission format: {}", mode)),
+                "{}: {}",
+                mode,
+                stderr
+            );
+            assert!(!stderr.contains("Unknown flag"), "{}: {}", mode, stderr);
+            assert!(!dir.exists());
+        }
+    }
+
+    #[test]
+    fn test_parents_mode_only_touches_created_parents() {
+        let temp_dir = tempdir().unwrap();
+        let existing = temp_dir.path().join("srv");
+        fs::create_dir(&existing).unwrap();
+        fs::set_permissions(&existing, fs::Permissions::from_mode(0o755)).unwrap();
+        let leaf = existing.join("www/site/public");
+
+        let (code, _, _) = run_drako(&[leaf.to_str().unwrap(), "-750", "--parents-mode", "711"]);
+        assert_eq!(code, Some(0));
+        assert_eq!(mode_of(&leaf), 0o750);
+        assert_eq!(mode_of(&existing.join("www/site")), 0o711);
+        assert_eq!(mode_of(&existing.join("www")), 0o711);
+        assert_eq!(mode_of(&existing), 0o755);
+    }
+
+    #[test]
+    fn test_symbolic_clauses() {
+        assert_eq!(apply("u=rwx,g=rx,o=", 0o777, true), 0o750);
+        assert_eq!(apply("g+w", 0o755, true), 0o775);
+        assert_eq!(apply("a-w", 0o664, false), 0o444);
+        assert_eq!(apply("+t", 0o777, true), 0o1777);
+        assert_eq!(apply("g+s,o-rwx", 0o775, true), 0o2770);
+        assert_eq!(apply("go=u-w", 0o700, true), 0o755);
+        assert_eq!(apply("a+X", 0o644, true), 0o755);
+        assert_eq!(apply("a+X", 0o644, false), 0o644);
+        assert_eq!(apply("u=rw,u+x", 0o000, false), 0o700);
+    }
+
+    #[test]
+    fn test_octal_modes_replace_every_bit() {
+        assert_eq!(apply("750", 0o2777, true), 0o750);
+        assert_eq!(apply("2775", 0o700, true), 0o2775);
+        assert_eq!("0755".parse::<Mode>(), Ok(Mode::Octal(0o755)));
+        assert_eq!("2775".parse::<Mode>().unwrap().to_string(), "2775");
+        assert_eq!("g=u".parse::<Mode>().unwrap().to_string(), "g=u");
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        for spec in ["", "8", "77777", "u", "u=rwz", "x+r", "u+r,", "g=uo"] {
+            assert!(spec.parse::<Mode>().is_err(), "{:?}", spec);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::run_drako;
+use drako::naming::{self, Case};
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug_keeps_the_typed_name_for_templates() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("My New Service");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--slug", "-r"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        let readme = temp_dir.path().join("my-new-service/README.md");
+        let content = fs::read_to_string(readme).unwrap();
+        assert!(content.starts_with("# My New Service\n"), "{}", content);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_cargo_gets_the_typed_name() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("My Tool");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--case", "camel", "--cargo"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        let manifest = fs::read_to_string(temp_dir.path().join("MyTool/Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"my_tool\""), "{}", manifest);
+    }
+
+    #[test]
+    fn test_case_applies_to_tree_entries() {
+        let temp_dir = tempdir().unwrap();
+        let tree = format!(
+            "{}/Web App(Static Files,apiClient)",
+            temp_dir.path().display()
+        );
+
+        let (code, _, stderr) = run_drako(&[&tree, "--case", "snake"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        let app = temp_dir.path().join("web_app");
+        assert!(app.join("static_files").is_dir());
+        assert!(app.join("api_client").is_dir());
+
+        let (code, _, stderr) = run_drako(&["x", "--case", "title"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Unknown case: title"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_names_are_stored_in_nfc() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("Cafe\u{301}");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(temp_dir.path().join("Caf\u{e9}").is_dir());
+    }
+
+    #[test]
+    fn test_cases_and_normalization() {
+        assert_eq!(Case::Kebab.apply("My New Service"), "my-new-service");
+        assert_eq!(Case::Snake.apply("My New Service"), "my_new_service");
+        assert_eq!(Case::Camel.apply("My New Service"), "MyNewService");
+        assert_eq!(Case::Kebab.apply("HTTPServer v2"), "http-server-v2");
+        assert_eq!(Case::Snake.apply("myNewService"), "my_new_service");
+        assert_eq!(Case::Kebab.apply("Ünïcode Namé"), "ünïcode-namé");
+        assert_eq!(Case::Kebab.apply("--"), "--");
+
+        assert_eq!(naming::nfc("e\u{301}"), "\u{e9}");
+        // Marks in any order compose the same way.
+        assert_eq!(naming::nfc("e\u{323}\u{302}"), "\u{1ec7}");
+        assert_eq!(naming::nfc("e\u{302}\u{323}"), "\u{1ec7}");
+        assert_eq!(naming::nfc("\u{1100}\u{1161}\u{11a8}"), "\u{ac01}");
+        assert_eq!(naming::nfc("\u{304b}\u{3099}"), "\u{304c}");
+        assert_eq!(naming::nfc("\u{212b}"), "\u{c5}");
+        assert_eq!(naming::nfc("plain"), "plain");
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::run_drako;
+use drako::owner::{self, Owner};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_applies_to_directories_and_files() {
+        let temp_dir = tempdir().unwrap();
+        // Everyone may give their files to their own group.
+        let gid = fs::metadata(temp_dir.path()).unwrap().gid().to_string();
+        let dir = temp_dir.path().join("app");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "-r", "--group", &gid]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert_eq!(fs::metadata(&dir).unwrap().gid().to_string(), gid);
+        assert_eq!(
+            fs::metadata(dir.join("README.md"))
+                .unwrap()
+                .gid()
+                .to_string(),
+            gid
+        );
+    }
+
+    #[test]
+    fn test_owner_needs_permission() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("app");
+        let me = fs::metadata(temp_dir.path()).unwrap().uid();
+        let other = if me == 0 { 1 } else { 0 };
+
+        let (code, _, stderr) = run_drako(&[
+            dir.to_str().unwrap(),
+            "--owner",
+            &other.to_string(),
+            "--output",
+            "json",
+        ]);
+        assert!(dir.is_dir());
+        if me == 0 {
+            assert_eq!(code, Some(0), "{}", stderr);
+            assert_eq!(fs::metadata(&dir).unwrap().uid(), other);
+        } else {
+            assert_eq!(code, Some(4));
+        }
+    }
+
+    #[test]
+    fn test_group_applies_to_parents_and_command_output() {
+        let temp_dir = tempdir().unwrap();
+        let mut gid = fs::metadata(temp_dir.path()).unwrap().gid();
+        // Only root can pick a group it isn't in.
+        if fs::metadata(temp_dir.path()).unwrap().uid() == 0 {
+            gid += 1;
+        }
+        let dir = temp_dir.path().join("a/b/app");
+
+        let (code, _, stderr) =
+            run_drako(&[dir.to_str().unwrap(), "--git", "--group", &gid.to_string()]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        for path in ["a", "a/b", "a/b/app", "a/b/app/.git", "a/b/app/.git/HEAD"] {
+            let metadata = fs::metadata(temp_dir.path().join(path)).unwrap();
+            assert_eq!(metadata.gid(), gid, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_unknown_names_are_usage_errors() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("app");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--owner", "no-such-user"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("No such user: no-such-user"), "{}", stderr);
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--group", "no-such-group"]);
+        assert_eq!(code, Some(2));
+        assert!(
+            stderr.contains("No such group: no-such-group"),
+            "{}",
+            stderr
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_owner_specs() {
+        let owner: Owner = "1:2".parse().unwrap();
+        assert_eq!((owner.uid(), owner.gid()), (Some(1), Some(2)));
+        assert_eq!(owner.to_string(), "1:2");
+
+        let owner: Owner = ":0".parse().unwrap();
+        assert_eq!((owner.uid(), owner.gid()), (None, Some(0)));
+
+        let root: Owner = "root:".parse().unwrap();
+        assert_eq!(root.uid(), owner::user_id("root"));
+        assert_eq!(root.gid(), owner::group_id("root"));
+
+        assert!(":".parse::<Owner>().is_err());
+        assert!("".parse::<Owner>().is_err());
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{drako, output};
+use drako::placeholders::format_time;
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2023-11-14 22:13:20 UTC, in ISO week 2023-W46.
+    const EPOCH: &str = "1700000000";
+
+    fn run_drako(args: &[&str]) -> (Option<i32>, String, String) {
+        output(
+            drako()
+                .args(args)
+                .env("SOURCE_DATE_EPOCH", EPOCH)
+                .env("TZ", "UTC")
+                .env("USER", "alice"),
+        )
+    }
+
+    #[test]
+    fn test_date_placeholders() {
+        let temp_dir = tempdir().unwrap();
+        let base = temp_dir.path().display();
+
+        let (code, _, _) = run_drako(&[
+            &format!("{}/reports/%Y-%m-%d", base),
+            &format!("{}/meetings/%G-W%V", base),
+        ]);
+
+        assert_eq!(code, Some(0));
+        assert!(temp_dir.path().join("reports/2023-11-14").is_dir());
+        assert!(temp_dir.path().join("meetings/2023-W46").is_dir());
+    }
+
+    #[test]
+    fn test_other_percent_signs_are_kept() {
+        assert_eq!(format_time("x%20y", 0).unwrap(), "x%20y");
+        assert_eq!(format_time("a%%b", 0).unwrap(), "a%b");
+        assert_eq!(format_time("50%", 0).unwrap(), "50%");
+        assert_eq!(format_time("%Q%2F", 0).unwrap(), "%Q%2F");
+
+        let temp_dir = tempdir().unwrap();
+        let (code, _, stderr) = run_drako(&[&format!("{}/x%20y-%Y", temp_dir.path().display())]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(temp_dir.path().join("x%20y-2023").is_dir());
+    }
+
+    #[test]
+    fn test_user_placeholder() {
+        let temp_dir = tempdir().unwrap();
+
+        let (code, _, _) = run_drako(&[&format!("{}/home-{{user}}", temp_dir.path().display())]);
+
+        assert_eq!(code, Some(0));
+        assert!(temp_dir.path().join("home-alice").is_dir());
+    }
+
+    #[test]
+    fn test_sequence_finds_next_free_number() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("run-001")).unwrap();
+        let arg = format!("{}/run-{{seq:3}}", temp_dir.path().display());
+
+        let (code, _, _) = run_drako(&[&arg, &arg]);
+
+        assert_eq!(code, Some(0));
+        assert!(temp_dir.path().join("run-002").is_dir());
+        assert!(temp_dir.path().join("run-003").is_dir());
+    }
+
+    #[test]
+    fn test_no_expand_keeps_placeholders() {
+        let temp_dir = tempdir().unwrap();
+
+        let (code, _, _) = run_drako(&[
+            &format!("{}/%Y-{{user}}", temp_dir.path().display()),
+            "--no-expand",
+        ]);
+
+        assert_eq!(code, Some(0));
+        assert!(temp_dir.path().join("%Y-{user}").is_dir());
+    }
+}
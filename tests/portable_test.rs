@@ -0,0 +1,66 @@
+mod common;
+
+use common::run_drako;
+use drako::portable;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_portable_names_warn_by_default() {
+        let temp_dir = tempdir().unwrap();
+        let tree = format!("{}/app(CON,Docs,docs)", temp_dir.path().display());
+
+        let (code, _, stderr) = run_drako(&[&tree]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(
+            stderr.contains("CON is a reserved name on Windows"),
+            "{}",
+            stderr
+        );
+        assert!(stderr.contains("only differs in case from"), "{}", stderr);
+        assert!(temp_dir.path().join("app/CON").is_dir());
+    }
+
+    #[test]
+    fn test_portable_refuses_before_creating_anything() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("new/release notes");
+
+        let (code, _, stderr) = run_drako(&["--portable", dir.to_str().unwrap()]);
+        assert_eq!(code, Some(2));
+        assert!(
+            stderr.contains("outside the POSIX portable character set"),
+            "{}",
+            stderr
+        );
+        assert!(!temp_dir.path().join("new").exists());
+
+        let dir = temp_dir.path().join("release-notes_1.0");
+        let (code, _, stderr) = run_drako(&["--portable", dir.to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(!stderr.contains("Not a portable name"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_name_rules() {
+        assert!(portable::name_problems("src", true).is_empty());
+        assert!(portable::name_problems("My Documents", false).is_empty());
+        assert_eq!(portable::name_problems("My Documents", true).len(), 1);
+        assert_eq!(portable::name_problems("nul.txt", false).len(), 1);
+        assert_eq!(portable::name_problems("a:b", false).len(), 1);
+        assert_eq!(portable::name_problems("tab\there", false).len(), 1);
+        assert_eq!(portable::name_problems("trailing ", false).len(), 1);
+        assert_eq!(portable::name_problems("dot.", false).len(), 1);
+        assert_eq!(portable::name_problems(&"x".repeat(256), false).len(), 1);
+        assert!(portable::name_problems("CONSOLE", false).is_empty());
+
+        let long = PathBuf::from("a/".repeat(131));
+        assert_eq!(portable::check(&[long], false).len(), 1);
+        let paths = [PathBuf::from("app/Src"), PathBuf::from("app/src")];
+        assert_eq!(portable::check(&paths, false)[0].path, paths[1]);
+    }
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::run_drako;
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_directories() {
+        let temp_dir = tempdir().unwrap();
+        let gid = fs::metadata(temp_dir.path()).unwrap().gid();
+        let tree = format!("{}/team(docs)", temp_dir.path().display());
+
+        let (code, stdout, stderr) = run_drako(&[
+            &tree,
+            "-750",
+            "--shared",
+            &gid.to_string(),
+            "--output",
+            "json",
+        ]);
+        assert_eq!(code, Some(0), "{}", stderr);
+
+        for dir in ["team", "team/docs"] {
+            let metadata = fs::metadata(temp_dir.path().join(dir)).unwrap();
+            assert_eq!(metadata.gid(), gid);
+            assert_eq!(metadata.permissions().mode() & 0o7777, 0o2770, "{}", dir);
+        }
+
+        let acl = r#""action":"set_default_acl","path":"#;
+        assert_eq!(stdout.matches(acl).count(), 2, "{}", stdout);
+        if !stdout.contains("Default ACLs are not supported") {
+            // The default ACL beats the umask of whoever creates files later.
+            let file = temp_dir.path().join("team/docs/notes.txt");
+            fs::write(&file, "").unwrap();
+            assert_eq!(
+                fs::metadata(&file).unwrap().permissions().mode() & 0o070,
+                0o060
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_needs_a_known_group() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("team");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--shared", "no-such-group"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("No such group"), "{}", stderr);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::run_drako;
+use drako::skeleton;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A project with build output, dependencies and a .gitignore.
+    fn service(root: &Path) -> PathBuf {
+        let source = root.join("service-a");
+        for dir in [
+            "src/bin",
+            "target/debug",
+            "node_modules/pkg",
+            ".git/objects",
+            "docs",
+            "logs/archive",
+            "logs/keep",
+            "web/dist",
+        ] {
+            fs::create_dir_all(source.join(dir)).unwrap();
+        }
+        fs::write(source.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(source.join("docs/.gitkeep"), "").unwrap();
+        fs::write(
+            source.join(".gitignore"),
+            "# build output\ntarget/\n/node_modules\nlogs/*\n!logs/keep\n",
+        )
+        .unwrap();
+        fs::write(source.join("web/.gitignore"), "dist\n").unwrap();
+        source
+    }
+
+    #[test]
+    fn test_scan_honors_gitignore() {
+        let temp_dir = tempdir().unwrap();
+        let source = service(temp_dir.path());
+
+        let skeleton = skeleton::scan(&source, false).unwrap();
+
+        let paths: Vec<PathBuf> = skeleton.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                "docs",
+                "docs/.gitkeep",
+                "logs",
+                "logs/keep",
+                "src",
+                "src/bin",
+                "web"
+            ]
+            .map(PathBuf::from)
+        );
+        assert_eq!(skeleton.mode, None);
+    }
+
+    #[test]
+    fn test_like_recreates_layout() {
+        let temp_dir = tempdir().unwrap();
+        let source = service(temp_dir.path());
+        fs::set_permissions(source.join("docs"), fs::Permissions::from_mode(0o700)).unwrap();
+        let target = temp_dir.path().join("service-b");
+
+        let (code, _, _) = run_drako(&[
+            target.to_str().unwrap(),
+            "--like",
+            source.to_str().unwrap(),
+            "--copy-modes",
+        ]);
+
+        assert_eq!(code, Some(0));
+        assert!(target.join("src/bin").is_dir());
+        assert!(target.join("docs/.gitkeep").is_file());
+        assert!(!target.join("src/main.rs").exists());
+        assert!(!target.join("target").exists());
+        assert!(!target.join("node_modules").exists());
+        assert!(!target.join(".git").exists());
+        assert!(!target.join("web/dist").exists());
+        let mode = fs::metadata(target.join("docs"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn test_like_missing_source() {
+        let temp_dir = tempdir().unwrap();
+        let target = temp_dir.path().join("service-b");
+        let missing = temp_dir.path().join("missing");
+
+        let (code, _, stderr) = run_drako(&[
+            target.to_str().unwrap(),
+            "--like",
+            missing.to_str().unwrap(),
+        ]);
+
+        assert_eq!(code, Some(8));
+        assert!(stderr.contains("No such directory"));
+        assert!(!target.exists());
+    }
+}
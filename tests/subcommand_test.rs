@@ -0,0 +1,117 @@
+mod common;
+
+use common::{drako, output};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_drako(args: &[&str], config: &Path) -> (Option<i32>, String, String) {
+        output(drako().args(args).env("DRAKO_CONFIG", config))
+    }
+
+    #[test]
+    fn test_add_to_existing_directory() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("existing_project");
+        fs::create_dir(&test_dir).unwrap();
+        let config = temp_dir.path().join("config");
+
+        let (code, _, _) = run_drako(
+            &["add", test_dir.to_str().unwrap(), "--readme", "--mit"],
+            &config,
+        );
+
+        assert_eq!(code, Some(0));
+        assert!(test_dir.join("README.md").exists());
+        assert!(test_dir.join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_add_missing_directory() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("missing_project");
+        let config = temp_dir.path().join("config");
+
+        let (code, _, stderr) = run_drako(&["add", test_dir.to_str().unwrap(), "-r"], &config);
+
+        assert_eq!(code, Some(8));
+        assert!(stderr.contains("No such directory"));
+        assert!(!test_dir.exists(), "add should never create directories");
+    }
+
+    #[test]
+    fn test_new_subcommand() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("new_project");
+        let config = temp_dir.path().join("config");
+
+        let (code, _, _) = run_drako(&["new", test_dir.to_str().unwrap(), "-r"], &config);
+
+        assert_eq!(code, Some(0));
+        assert!(test_dir.join("README.md").exists());
+    }
+
+    #[test]
+    fn test_templates_list() {
+        let temp_dir = tempdir().unwrap();
+        let config = temp_dir.path().join("config");
+
+        let (code, stdout, _) = run_drako(&["templates", "list"], &config);
+
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("README.md"));
+        assert!(stdout.contains("LICENSE"));
+        assert!(stdout.contains("Dockerfile"));
+    }
+
+    #[test]
+    fn test_config_defaults_apply_to_new_projects() {
+        let temp_dir = tempdir().unwrap();
+        let config = temp_dir.path().join("config");
+        fs::write(&config, "# team defaults\nflags = --readme\nmode = 750\n").unwrap();
+        let test_dir = temp_dir.path().join("configured");
+
+        let (code, stdout, _) = run_drako(&["config", "show"], &config);
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("--readme"));
+        assert!(stdout.contains("750"));
+
+        let (code, _, _) = run_drako(&[test_dir.to_str().unwrap()], &config);
+        assert_eq!(code, Some(0));
+        assert!(test_dir.join("README.md").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&test_dir).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o750);
+        }
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let temp_dir = tempdir().unwrap();
+        let config = temp_dir.path().join("config");
+        fs::write(&config, "colour = blue\n").unwrap();
+
+        let (code, _, stderr) = run_drako(&["config", "show"], &config);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains(":1: unknown setting `colour`"));
+    }
+
+    #[test]
+    fn test_unknown_subcommand_action() {
+        let temp_dir = tempdir().unwrap();
+        let config = temp_dir.path().join("config");
+
+        let (code, _, stderr) = run_drako(&["templates", "remove"], &config);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Unknown subcommand"));
+    }
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::run_drako;
+use drako::tree::{self, TreeNode};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(name: &str) -> TreeNode {
+        TreeNode {
+            name: name.to_string(),
+            children: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_nested_tree() {
+        let nodes = tree::parse(r#"app(src(bin), "odd, name", 'x(y)'), docs"#).unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                TreeNode {
+                    name: "app".to_string(),
+                    children: vec![
+                        TreeNode {
+                            name: "src".to_string(),
+                            children: vec![leaf("bin")],
+                        },
+                        leaf("odd, name"),
+                        leaf("x(y)"),
+                    ],
+                },
+                leaf("docs"),
+            ]
+        );
+        assert_eq!(
+            nodes[0].descendants(),
+            vec![
+                PathBuf::from("src"),
+                PathBuf::from("src/bin"),
+                PathBuf::from("odd, name"),
+                PathBuf::from("x(y)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_column() {
+        let cases = [
+            ("app(src,lib", 4, "never closed"),
+            ("app(src,,lib)", 9, "expected a directory name"),
+            ("app(src))", 9, "unmatched `)`"),
+            ("app(\"src)", 5, "quote is never closed"),
+            ("app(..)", 5, "not allowed"),
+            ("app(src, /tmp/x)", 10, "must be relative"),
+        ];
+        for (input, column, message) in cases {
+            let err = tree::parse(input).unwrap_err();
+            assert_eq!(err.column, column, "{}", input);
+            assert!(err.message.contains(message), "{}: {}", input, err.message);
+        }
+    }
+
+    #[test]
+    fn test_create_tree() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("app");
+        let expr = format!("{}(src(bin, lib), public, tests)", root.display());
+
+        let (code, _, _) = run_drako(&[&expr, "--readme"]);
+
+        assert_eq!(code, Some(0));
+        for dir in ["src", "src/bin", "src/lib", "public", "tests"] {
+            assert!(root.join(dir).is_dir(), "{} should exist", dir);
+        }
+        assert!(root.join("README.md").exists());
+        assert!(!root.join("src/README.md").exists());
+    }
+
+    #[test]
+    fn test_invalid_tree_creates_nothing() {
+        let temp_dir = tempdir().unwrap();
+        let good = temp_dir.path().join("good");
+        let bad = format!("{}(src", temp_dir.path().join("bad").display());
+
+        let (code, _, stderr) = run_drako(&[good.to_str().unwrap(), &bad]);
+
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("never closed"));
+        assert!(stderr.contains('^'));
+        assert!(!good.exists());
+    }
+
+    #[test]
+    fn test_absolute_child_does_not_escape() {
+        let temp_dir = tempdir().unwrap();
+        let escape = temp_dir.path().join("escape");
+        let expr = format!(
+            "{}(src, {})",
+            temp_dir.path().join("app").display(),
+            escape.display()
+        );
+
+        let (code, _, stderr) = run_drako(&[&expr]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("must be relative"), "{}", stderr);
+        assert!(!escape.exists());
+        assert!(!temp_dir.path().join("app").exists());
+    }
+
+    #[test]
+    fn test_parentheses_after_a_space_are_part_of_the_name() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("Photos (2024)");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(dir.is_dir());
+        assert!(!temp_dir.path().join("Photos").exists());
+        assert!(!tree::is_tree("Photos (2024)"));
+        assert!(tree::is_tree("app(src)"));
+    }
+}
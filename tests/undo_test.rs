@@ -0,0 +1,163 @@
+mod common;
+
+use common::{drako, output};
+use drako::journal::MAX_RUNS;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_drako(state: &Path, args: &[&str]) -> (Option<i32>, String, String) {
+        output(drako().args(args).env("XDG_STATE_HOME", state))
+    }
+
+    fn journal(state: &Path) -> Vec<String> {
+        match fs::read_dir(state.join("drako/journal")) {
+            Ok(entries) => entries
+                .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_undo_removes_what_the_last_run_created() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let tree = format!("{}/a/b/svc(src,tests)", temp_dir.path().display());
+
+        let (code, _, stderr) = run_drako(state.path(), &[&tree, "-r"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert_eq!(journal(state.path()).len(), 1);
+
+        let (code, stdout, stderr) = run_drako(state.path(), &["undo"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(stdout.contains("removed 6 of 6 paths"), "{}", stdout);
+        assert!(fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+        assert!(journal(state.path()).is_empty());
+
+        let (code, _, stderr) = run_drako(state.path(), &["undo"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Nothing to undo"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_undo_keeps_changed_files() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let app = temp_dir.path().join("app");
+        let lib = temp_dir.path().join("lib");
+
+        run_drako(state.path(), &[app.to_str().unwrap(), "-r"]);
+        run_drako(state.path(), &[lib.to_str().unwrap(), "-r"]);
+        fs::write(lib.join("README.md"), "edited by hand\n").unwrap();
+        fs::write(lib.join("notes.txt"), "not from drako\n").unwrap();
+
+        let (code, _, stderr) = run_drako(state.path(), &["undo"]);
+        assert_eq!(code, Some(9));
+        assert!(
+            stderr.contains("it was changed after drako created it"),
+            "{}",
+            stderr
+        );
+        assert!(stderr.contains("it holds files drako didn't create"));
+        assert!(lib.join("README.md").exists());
+        assert!(app.exists());
+
+        // What was kept stays in the journal for another try.
+        let (_, stdout, _) = run_drako(state.path(), &["undo", "list"]);
+        assert_eq!(stdout.lines().count(), 2, "{}", stdout);
+        assert!(stdout.lines().next().unwrap().contains("lib -r"));
+    }
+
+    #[test]
+    fn test_undo_a_chosen_run() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let app = temp_dir.path().join("app");
+        let lib = temp_dir.path().join("lib");
+
+        run_drako(state.path(), &[app.to_str().unwrap()]);
+        run_drako(state.path(), &[lib.to_str().unwrap()]);
+        let (_, stdout, _) = run_drako(state.path(), &["undo", "list"]);
+        let oldest = stdout.lines().last().unwrap();
+        assert!(oldest.contains("app"), "{}", stdout);
+        // The id comes first, in green.
+        let id = oldest
+            .split('\x1b')
+            .nth(1)
+            .unwrap()
+            .trim_start_matches("[1;32m");
+
+        let (code, _, stderr) = run_drako(state.path(), &["undo", id]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert!(!app.exists());
+        assert!(lib.exists());
+
+        let (code, _, stderr) = run_drako(state.path(), &["undo", "no-such-run"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("No such run"), "{}", stderr);
+    }
+
+    #[test]
+    fn test_undo_restores_backups() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let readme = temp_dir.path().join("README.md");
+        fs::write(&readme, "the original\n").unwrap();
+
+        let (code, _, stderr) = run_drako(
+            state.path(),
+            &[
+                "add",
+                temp_dir.path().to_str().unwrap(),
+                "-r",
+                "--conflict",
+                "backup",
+            ],
+        );
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert_ne!(fs::read_to_string(&readme).unwrap(), "the original\n");
+
+        let (code, _, stderr) = run_drako(state.path(), &["undo"]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        assert_eq!(fs::read_to_string(&readme).unwrap(), "the original\n");
+        assert!(!temp_dir.path().join("README.md.bak").exists());
+    }
+
+    #[test]
+    fn test_journal_forgets_the_oldest_runs() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let journal_dir = state.path().join("drako/journal");
+        fs::create_dir_all(&journal_dir).unwrap();
+        for i in 0..MAX_RUNS {
+            fs::write(
+                journal_dir.join(format!("{}-1", 1000 + i)),
+                format!("time\t{}\ncwd\t/\nargs\told\n", 1000 + i),
+            )
+            .unwrap();
+        }
+
+        let app = temp_dir.path().join("app");
+        let (code, _, stderr) = run_drako(state.path(), &[app.to_str().unwrap()]);
+        assert_eq!(code, Some(0), "{}", stderr);
+        let runs = journal(state.path());
+        assert_eq!(runs.len(), MAX_RUNS);
+        assert!(!runs.contains(&"1000-1".to_string()));
+        assert!(runs.contains(&"1001-1".to_string()));
+    }
+
+    #[test]
+    fn test_dry_runs_are_not_recorded() {
+        let state = tempdir().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let app = temp_dir.path().join("app");
+
+        run_drako(state.path(), &[app.to_str().unwrap(), "--dry-run"]);
+        assert!(journal(state.path()).is_empty());
+    }
+}
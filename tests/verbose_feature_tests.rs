@@ -1,3 +1,6 @@
+mod common;
+
+use common::drako;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
@@ -22,9 +25,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--verbose")
             .output()
@@ -46,9 +47,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&nested_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(nested_dir.to_str().unwrap())
             .arg("--verbose")
             .output()
@@ -72,9 +71,7 @@ mod tests {
         cleanup_test_dir(&dir1);
         cleanup_test_dir(&dir2);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(dir1.to_str().unwrap())
             .arg(dir2.to_str().unwrap())
             .arg("--verbose")
@@ -98,9 +95,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-755")
             .arg("--verbose")
@@ -127,9 +122,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--readme")
             .arg("--verbose")
@@ -153,9 +146,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--license")
             .arg("--verbose")
@@ -179,9 +170,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--docker")
             .arg("--verbose")
@@ -205,9 +194,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("-v")  // Short flag version
             .output()
@@ -229,15 +216,13 @@ mod tests {
         // Create the directory first
         fs::create_dir(&test_dir).unwrap();
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--verbose")
             .output()
             .unwrap();
 
-        assert!(output.status.success(), "Command failed: {}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(output.status.code(), Some(3), "Existing directory should exit with code 3");
         
         // Check that verbose output mentions directory already exists
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -264,9 +249,7 @@ mod tests {
         // Make sure the directory doesn't exist
         cleanup_test_dir(&test_dir);
 
-        let output = Command::new("cargo")
-            .arg("run")
-            .arg("--")
+        let output = drako()
             .arg(test_dir.to_str().unwrap())
             .arg("--git")
             .arg("--verbose")
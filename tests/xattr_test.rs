@@ -0,0 +1,101 @@
+mod common;
+
+use common::run_drako;
+use drako::xattr;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xattrs_follow_created_directories() {
+        let temp_dir = tempdir().unwrap();
+        let tree = format!("{}/app(src)", temp_dir.path().display());
+
+        let (_, stdout, _) = run_drako(&[
+            &tree,
+            "-r",
+            "--xattr",
+            "user.project=atlas",
+            "--xattr",
+            "user.owner-team=infra",
+            "--output",
+            "json",
+        ]);
+        let actions: Vec<&str> = stdout
+            .lines()
+            .map(|line| line.split(',').next().unwrap())
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                r#"{"action":"create_dir""#,
+                r#"{"action":"set_xattr""#,
+                r#"{"action":"set_xattr""#,
+                r#"{"action":"create_dir""#,
+                r#"{"action":"set_xattr""#,
+                r#"{"action":"set_xattr""#,
+                r#"{"action":"write_file""#,
+            ]
+        );
+        assert!(stdout.contains(r#""name":"user.project","value":"atlas""#));
+    }
+
+    #[test]
+    fn test_xattr_files() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("app");
+
+        let (_, stdout, _) = run_drako(&[
+            dir.to_str().unwrap(),
+            "-r",
+            "--xattr",
+            "user.project=atlas",
+            "--xattr-files",
+            "--output",
+            "json",
+        ]);
+        assert!(
+            stdout.contains(&format!(
+                r#"{{"action":"set_xattr","path":"{}/README.md""#,
+                dir.display()
+            )),
+            "{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_rejected_attributes_are_reported() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("app");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--xattr", "nosuch.tag=1"]);
+        assert_eq!(code, Some(1));
+        assert!(
+            stderr.contains("the file system rejected nosuch.tag"),
+            "{}",
+            stderr
+        );
+        assert!(dir.is_dir());
+    }
+
+    #[test]
+    fn test_xattr_values_are_validated() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("app");
+
+        let (code, _, stderr) = run_drako(&[dir.to_str().unwrap(), "--xattr", "project"]);
+        assert_eq!(code, Some(2));
+        assert!(stderr.contains("Invalid extended attribute"), "{}", stderr);
+        assert!(!dir.exists());
+
+        assert_eq!(
+            xattr::parse("user.note=a=b"),
+            Ok(("user.note".to_string(), "a=b".to_string()))
+        );
+        assert!(xattr::parse("=x").is_err());
+        assert!(xattr::parse(".x=1").is_err());
+    }
+}